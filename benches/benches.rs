@@ -1,13 +1,35 @@
-#![feature(test)]
-
-extern crate test;
+extern crate criterion;
 extern crate wasm_game_of_life;
 
-#[bench]
-fn universe_ticks(b: &mut test::Bencher) {
-    let mut universe = wasm_game_of_life::Universe::new();
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use wasm_game_of_life::Universe;
+
+// A fixed soup at 50% density, so runs are comparable.
+fn soup(width: u32, height: u32) -> Universe {
+    let mut universe = Universe::new(width, height);
+    universe.randomize_with(0x2545_f491_4f6c_dd1d, 0.5, None);
+    universe
+}
+
+fn universe_ticks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("universe_ticks");
 
-    b.iter(|| {
-        universe.tick();
-    });
+    for (width, height) in [(96, 64), (1024, 1024)].iter().cloned() {
+        let size = format!("{}x{}", width, height);
+
+        let mut universe = soup(width, height);
+        group.bench_function(BenchmarkId::new("bitwise", &size), |b| b.iter(|| {
+            universe.tick();
+        }));
+
+        let mut universe = soup(width, height);
+        group.bench_function(BenchmarkId::new("scalar", &size), |b| b.iter(|| {
+            universe.tick_scalar();
+        }));
+    }
+
+    group.finish();
 }
+
+criterion_group!(benches, universe_ticks);
+criterion_main!(benches);
@@ -0,0 +1,315 @@
+//! An interactive viewer that runs in a terminal: `life-tui [OPTIONS] [PATTERN]`.
+//! It draws whatever part of the universe fits in the terminal, one character
+//! per cell, and works with any universe that implements `Grid`.
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, terminal};
+
+use wasm_game_of_life::{parse_pattern, Grid, Pattern, Random, Rule, SparseUniverse, Universe};
+
+const USAGE: &str = "\
+Usage: life-tui [OPTIONS] [PATTERN]
+
+Runs the Game of Life in the terminal, starting from a pattern file or a
+random soup.
+
+Options:
+  -r, --rule <RULE>   Rule such as B36/S23 [default: the pattern's, or B3/S23]
+  -s, --size <WxH>    Universe size [default: fill the terminal]
+  -u, --unbounded     Run on an unbounded plane, which scrolls to follow the
+                      cursor, instead of a torus
+  -h, --help          Print this message
+
+Keys:
+  space        play or pause
+  n            step one generation while paused
+  + and -      speed up or slow down
+  r            randomize
+  c            clear
+  arrows/hjkl  move the cursor
+  enter/t      toggle the cell under the cursor
+  q or Esc     quit
+";
+
+// Ticks per second for each speed setting
+const SPEEDS: &[u32] = &[1, 2, 5, 10, 20, 30, 60];
+const DEFAULT_SPEED: usize = 3;
+
+// Rows of the terminal below the universe, for the status and help lines
+const STATUS_ROWS: u16 = 2;
+
+struct Viewer {
+    universe: Box<dyn Grid>,
+    playing: bool,
+    speed: usize,
+    cursor: (i64, i64),
+    // The cell shown in the terminal's top-left corner
+    origin: (i64, i64),
+}
+
+// Puts the terminal back the way it was when dropped, even after a panic.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::SetCursorStyle::SteadyBlock)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::SetCursorStyle::DefaultUserShape, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() {
+    let universe = match parse_args(env::args().skip(1)) {
+        Ok(Some(universe)) => universe,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let (columns, rows) = view_size();
+    let cursor = match universe.size() {
+        Some((width, height)) => (height as i64 / 2, width as i64 / 2),
+        None => (rows as i64 / 2, columns as i64 / 2),
+    };
+    let mut viewer = Viewer {
+        universe,
+        playing: false,
+        speed: DEFAULT_SPEED,
+        cursor,
+        origin: (0, 0),
+    };
+    viewer.move_cursor(cursor.0, cursor.1);
+
+    let result = RawTerminal::enter().and_then(|_terminal| viewer.run());
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+// The universe to start with, or None if help was asked for.
+fn parse_args<I>(mut args: I) -> Result<Option<Box<dyn Grid>>, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input = None;
+    let mut rule = None;
+    let mut size = None;
+    let mut unbounded = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-r" | "--rule" => {
+                let value = value()?;
+                rule = Some(Rule::parse(&value).map_err(|error| format!("invalid rule {}: {}", value, error))?);
+            }
+            "-s" | "--size" => {
+                let value = value()?;
+                let invalid = || format!("invalid size {}, expected WIDTHxHEIGHT", value);
+                let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+                size = Some((width.parse().map_err(|_| invalid())?, height.parse().map_err(|_| invalid())?));
+            }
+            "-u" | "--unbounded" => unbounded = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ if input.is_some() => return Err(format!("unexpected argument {}", arg)),
+            _ => input = Some(arg),
+        }
+    }
+    if unbounded && size.is_some() {
+        return Err(String::from("an unbounded universe has no size"));
+    }
+
+    let pattern: Option<Pattern> = match input {
+        Some(path) => {
+            let text = fs::read_to_string(&path).map_err(|error| format!("couldn't read {}: {}", path, error))?;
+            Some(parse_pattern(&text).map_err(|error| format!("invalid pattern in {}: {}", path, error))?)
+        }
+        None => None,
+    };
+
+    let (width, height) = match size {
+        Some(size) => size,
+        None => {
+            let (columns, rows) = terminal::size().map_err(|error| format!("couldn't get the terminal size: {}", error))?;
+            (columns.max(1) as u32, rows.saturating_sub(STATUS_ROWS).max(1) as u32)
+        }
+    };
+    if width == 0 || height == 0 {
+        return Err(String::from("the universe needs at least one row and column"));
+    }
+
+    let rule = rule.or_else(|| pattern.as_ref().and_then(|pattern| pattern.rule)).unwrap_or_else(Rule::conway);
+    let row = height.saturating_sub(pattern.as_ref().map_or(0, Pattern::height)) / 2;
+    let column = width.saturating_sub(pattern.as_ref().map_or(0, Pattern::width)) / 2;
+
+    let mut universe: Box<dyn Grid> = if unbounded {
+        let mut universe = SparseUniverse::new(rule).map_err(|error| error.to_string())?;
+        if let Some(pattern) = &pattern {
+            universe.load_pattern(pattern, row as i64, column as i64);
+        }
+        Box::new(universe)
+    } else {
+        let mut universe = Universe::with_rule(width, height, rule);
+        if let Some(pattern) = &pattern {
+            universe.load_pattern(pattern, row, column);
+        }
+        Box::new(universe)
+    };
+    if pattern.is_none() {
+        randomize(universe.as_mut(), 0, 0, height, width);
+    }
+    Ok(Some(universe))
+}
+
+// The columns and rows of the terminal that show cells.
+fn view_size() -> (u16, u16) {
+    let (columns, rows) = terminal::size().unwrap_or((80, 24));
+    (columns.max(1), rows.saturating_sub(STATUS_ROWS).max(1))
+}
+
+// Fills a region with a soup at 50% density.
+fn randomize(universe: &mut dyn Grid, top: i64, left: i64, height: u32, width: u32) {
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+    let mut random = Random::new(seed);
+    for row in top..top + height as i64 {
+        for column in left..left + width as i64 {
+            universe.set_cell(row, column, random.next_f64() < 0.5);
+        }
+    }
+}
+
+impl Viewer {
+    fn run(&mut self) -> io::Result<()> {
+        let mut next_tick = Instant::now();
+        loop {
+            self.draw()?;
+
+            let timeout = if self.playing {
+                next_tick.saturating_duration_since(Instant::now())
+            } else {
+                Duration::from_secs(60)
+            };
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Release && !self.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            if self.playing && Instant::now() >= next_tick {
+                self.universe.tick();
+                next_tick = Instant::now() + Duration::from_secs(1) / SPEEDS[self.speed];
+            }
+        }
+    }
+
+    // Returns false to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let (row, column) = self.cursor;
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char('n') if !self.playing => self.universe.tick(),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed + 1).min(SPEEDS.len() - 1),
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Char('r') => {
+                let (top, left, height, width) = match self.universe.size() {
+                    Some((width, height)) => (0, 0, height, width),
+                    None => {
+                        let (columns, rows) = view_size();
+                        (self.origin.0, self.origin.1, rows as u32, columns as u32)
+                    }
+                };
+                randomize(self.universe.as_mut(), top, left, height, width);
+            }
+            KeyCode::Char('c') => self.universe.clear(),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(row - 1, column),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(row + 1, column),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(row, column - 1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(row, column + 1),
+            KeyCode::Enter | KeyCode::Char('t') => self.universe.toggle_cell(row, column),
+            _ => {}
+        }
+        true
+    }
+
+    // The cursor wraps around the edges of a bounded universe. The view
+    // scrolls to keep it on screen.
+    fn move_cursor(&mut self, row: i64, column: i64) {
+        let (row, column) = match self.universe.size() {
+            Some((width, height)) => (row.rem_euclid(height as i64), column.rem_euclid(width as i64)),
+            None => (row, column),
+        };
+        let (columns, rows) = view_size();
+        self.cursor = (row, column);
+        self.origin.0 = self.origin.0.clamp(row - rows as i64 + 1, row);
+        self.origin.1 = self.origin.1.clamp(column - columns as i64 + 1, column);
+    }
+
+    // Redraws everything in place, then puts the terminal's cursor on the
+    // selected cell.
+    fn draw(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        let (columns, rows) = view_size();
+        let (top, left) = self.origin;
+        let (width, height) = match self.universe.size() {
+            Some((width, height)) => (
+                (width as i64 - left).clamp(0, columns as i64) as u32,
+                (height as i64 - top).clamp(0, rows as i64) as u32,
+            ),
+            None => (columns as u32, rows as u32),
+        };
+        let state = if self.playing { "Playing" } else { "Paused" };
+
+        queue!(stdout, cursor::Hide, cursor::MoveTo(0, 0))?;
+        for line in self.universe.text_rows(top, left, height, width) {
+            write!(stdout, "{}", line)?;
+            queue!(stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+            write!(stdout, "\r\n")?;
+        }
+        queue!(stdout, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        write!(
+            stdout,
+            "Generation {}  Population {}  {} at {}/s  Cursor ({}, {})",
+            self.universe.generation(),
+            self.universe.population(),
+            state,
+            SPEEDS[self.speed],
+            self.cursor.0,
+            self.cursor.1
+        )?;
+        queue!(stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        write!(stdout, "\r\nspace play/pause  n step  +/- speed  r randomize  c clear  enter toggle  q quit")?;
+        queue!(stdout, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+
+        // The cursor can be off screen after the terminal shrinks
+        let (cursor_row, cursor_column) = (self.cursor.0 - top, self.cursor.1 - left);
+        if (0..height as i64).contains(&cursor_row) && (0..width as i64).contains(&cursor_column) {
+            queue!(stdout, cursor::MoveTo(cursor_column as u16, cursor_row as u16), cursor::Show)?;
+        }
+        stdout.flush()
+    }
+}
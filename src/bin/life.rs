@@ -0,0 +1,308 @@
+//! Runs a pattern headlessly: `life [OPTIONS] <PATTERN>`. See `USAGE` for the
+//! options.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+
+use wasm_game_of_life::{
+    parse_pattern, write_png, GifWriter, ImageStyle, Pattern, PatternFormat, Rect, Rule, Stability, Topology, Universe,
+};
+
+const USAGE: &str = "\
+Usage: life [OPTIONS] <PATTERN>
+
+Runs a pattern file (RLE, plaintext, Life 1.05/1.06 or macrocell), or - for
+standard input, and prints statistics about the result.
+
+Options:
+  -g, --generations <N>   Run at most N generations [default: 1000]
+  -u, --until-stable      Stop as soon as the universe is static, oscillating
+                          or translating
+  -r, --rule <RULE>       Rule such as B36/S23 [default: the pattern's, or B3/S23]
+  -t, --topology <NAME>   torus, bounded, klein-bottle or cross-surface
+                          [default: torus]
+  -s, --size <WxH>        Universe size [default: the pattern with a margin of
+                          32 cells on every side]
+  -o, --output <FILE>     Write the result, as RLE unless the extension is
+                          .cells, .lif or .mc; - for standard output
+  --png <FILE>            Save a picture of the result
+  --gif <FILE>            Save an animation of every generation run
+  -j, --json              Print statistics as JSON
+  -h, --help              Print this message
+";
+
+const MARGIN: u32 = 32;
+const GIF_DELAY_MS: u32 = 100;
+
+// Bad arguments, including a universe too large to make, exit with 2 and
+// anything else that goes wrong with 1.
+struct Failure {
+    code: i32,
+    message: String,
+}
+
+impl From<String> for Failure {
+    fn from(message: String) -> Failure {
+        Failure { code: 1, message }
+    }
+}
+
+struct Options {
+    input: String,
+    generations: u64,
+    until_stable: bool,
+    rule: Option<Rule>,
+    topology: Topology,
+    size: Option<(u32, u32)>,
+    output: Option<String>,
+    png: Option<String>,
+    gif: Option<String>,
+    json: bool,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(failure) = run(&options) {
+        eprintln!("error: {}", failure.message);
+        process::exit(failure.code);
+    }
+}
+
+// The options, or None if help was asked for.
+fn parse_args<I>(mut args: I) -> Result<Option<Options>, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input = None;
+    let mut options = Options {
+        input: String::new(),
+        generations: 1000,
+        until_stable: false,
+        rule: None,
+        topology: Topology::Torus,
+        size: None,
+        output: None,
+        png: None,
+        gif: None,
+        json: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-g" | "--generations" => {
+                let generations = value()?;
+                options.generations = generations
+                    .parse()
+                    .map_err(|_| format!("invalid number of generations: {}", generations))?;
+            }
+            "-u" | "--until-stable" => options.until_stable = true,
+            "-r" | "--rule" => {
+                let rule = value()?;
+                options.rule = Some(Rule::parse(&rule).map_err(|error| format!("invalid rule {}: {}", rule, error))?);
+            }
+            "-t" | "--topology" => options.topology = parse_topology(&value()?)?,
+            "-s" | "--size" => options.size = Some(parse_size(&value()?)?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "--png" => options.png = Some(value()?),
+            "--gif" => options.gif = Some(value()?),
+            "-j" | "--json" => options.json = true,
+            _ if arg.starts_with('-') && arg != "-" => return Err(format!("unknown option {}", arg)),
+            _ if input.is_some() => return Err(format!("unexpected argument {}", arg)),
+            _ => input = Some(arg),
+        }
+    }
+
+    options.input = input.ok_or("no pattern file given")?;
+    Ok(Some(options))
+}
+
+fn parse_topology(name: &str) -> Result<Topology, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "torus" => Ok(Topology::Torus),
+        "bounded" => Ok(Topology::Bounded),
+        "klein-bottle" => Ok(Topology::KleinBottle),
+        "cross-surface" => Ok(Topology::CrossSurface),
+        _ => Err(format!("unknown topology {}", name)),
+    }
+}
+
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("invalid size {}, expected WIDTHxHEIGHT", size);
+    let (width, height) = size.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width = width.parse().map_err(|_| invalid())?;
+    let height = height.parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+fn run(options: &Options) -> Result<(), Failure> {
+    let text = if options.input == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map_err(|error| format!("couldn't read standard input: {}", error))?;
+        text
+    } else {
+        fs::read_to_string(&options.input).map_err(|error| format!("couldn't read {}: {}", options.input, error))?
+    };
+    let pattern = parse_pattern(&text).map_err(|error| format!("invalid pattern in {}: {}", options.input, error))?;
+
+    let rule = options.rule.or(pattern.rule).unwrap_or_else(Rule::conway);
+    let too_large = |message: String| Failure { code: 2, message };
+    let (width, height) = match options.size {
+        Some(size) => size,
+        None => pattern
+            .width()
+            .checked_add(2 * MARGIN)
+            .zip(pattern.height().checked_add(2 * MARGIN))
+            .ok_or_else(|| too_large(format!("universe too large for the {}x{} pattern", pattern.width(), pattern.height())))?,
+    };
+    if pattern.width() > width || pattern.height() > height {
+        return Err(Failure::from(format!(
+            "the {}x{} pattern doesn't fit in a {}x{} universe",
+            pattern.width(),
+            pattern.height(),
+            width,
+            height
+        )));
+    }
+
+    let mut universe = Universe::try_with_rule_and_topology(width, height, rule, options.topology)
+        .map_err(|error| too_large(format!("universe too large: {}", error)))?;
+    universe.set_cycle_detection(true);
+    universe.load_pattern(&pattern, (height - pattern.height()) / 2, (width - pattern.width()) / 2);
+
+    let mut gif = options.gif.as_ref().map(|_| GifWriter::new(ImageStyle::default(), GIF_DELAY_MS));
+    if let Some(gif) = &mut gif {
+        gif.add_frame(&universe).map_err(|error| format!("couldn't make a GIF: {}", error))?;
+    }
+    for _ in 0..options.generations {
+        if options.until_stable && universe.stability().is_some_and(|stability| stability.is_settled()) {
+            break;
+        }
+        universe.tick();
+        if let Some(gif) = &mut gif {
+            gif.add_frame(&universe).map_err(|error| format!("couldn't make a GIF: {}", error))?;
+        }
+    }
+
+    if let Some(output) = &options.output {
+        write_result(&universe, &pattern, output)?;
+    }
+    if let Some(path) = &options.png {
+        let png = write_png(&universe, &ImageStyle::default()).map_err(|error| format!("couldn't make a PNG: {}", error))?;
+        write_file(path, &png)?;
+    }
+    if let (Some(path), Some(gif)) = (&options.gif, gif) {
+        let gif = gif.finish().map_err(|error| format!("couldn't make a GIF: {}", error))?;
+        write_file(path, &gif)?;
+    }
+
+    let stability = universe.stability().unwrap_or(Stability::Evolving);
+    let report = if options.json {
+        json_report(&universe, stability)
+    } else {
+        text_report(&universe, stability)
+    };
+    // Statistics go to standard error when the pattern is going to standard
+    // output, so the two don't mix
+    if options.output.as_deref() == Some("-") {
+        eprint!("{}", report);
+    } else {
+        print!("{}", report);
+    }
+    Ok(())
+}
+
+fn write_result(universe: &Universe, original: &Pattern, output: &str) -> Result<(), String> {
+    let format = match Path::new(output).extension().and_then(|extension| extension.to_str()) {
+        Some("cells") => PatternFormat::Plaintext,
+        Some("lif") | Some("life") => PatternFormat::Life106,
+        Some("mc") => PatternFormat::Macrocell,
+        _ => PatternFormat::Rle,
+    };
+
+    let mut pattern = universe.to_pattern();
+    pattern.name = original.name.clone();
+    pattern.author = original.author.clone();
+    pattern.comments = original.comments.clone();
+    pattern.comments.push(format!("Generation {}", universe.generation()));
+    let text = format.write(&pattern);
+
+    if output == "-" {
+        print!("{}", text);
+        Ok(())
+    } else {
+        write_file(output, text.as_bytes())
+    }
+}
+
+fn write_file(path: &str, contents: &[u8]) -> Result<(), String> {
+    fs::write(path, contents).map_err(|error| format!("couldn't write {}: {}", path, error))
+}
+
+fn text_report(universe: &Universe, stability: Stability) -> String {
+    let bounding_box = match universe.bounding_box() {
+        Some(rect) => format!("{}x{} at row {}, column {}", rect.width, rect.height, rect.row, rect.column),
+        None => String::from("none"),
+    };
+    let period = match stability.period() {
+        Some(period) => period.to_string(),
+        None => String::from("none"),
+    };
+
+    format!(
+        "generation: {}\npopulation: {}\nbounding box: {}\nperiod: {}\nstability: {}\n",
+        universe.generation(),
+        universe.population(),
+        bounding_box,
+        period,
+        stability
+    )
+}
+
+fn json_report(universe: &Universe, stability: Stability) -> String {
+    let bounding_box = match universe.bounding_box() {
+        Some(Rect { row, column, width, height }) => format!(
+            "{{\"top\":{},\"left\":{},\"width\":{},\"height\":{}}}",
+            row, column, width, height
+        ),
+        None => String::from("null"),
+    };
+    let period = match stability.period() {
+        Some(period) => period.to_string(),
+        None => String::from("null"),
+    };
+    let (kind, since, displacement) = match stability {
+        Stability::Evolving => ("evolving", None, None),
+        Stability::Static { since } => ("static", Some(since), None),
+        Stability::Oscillating { since, .. } => ("oscillating", Some(since), None),
+        Stability::Translating { dx, dy, since, .. } => ("translating", Some(since), Some((dx, dy))),
+    };
+    let since = since.map_or(String::from("null"), |since| since.to_string());
+    let displacement = displacement.map_or(String::from("null"), |(dx, dy)| format!("{{\"dx\":{},\"dy\":{}}}", dx, dy));
+
+    format!(
+        "{{\"generation\":{},\"population\":{},\"bounding_box\":{},\"period\":{},\"stability\":\"{}\",\"since\":{},\"displacement\":{}}}\n",
+        universe.generation(),
+        universe.population(),
+        bounding_box,
+        period,
+        kind,
+        since,
+        displacement
+    )
+}
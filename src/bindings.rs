@@ -0,0 +1,239 @@
+use std::mem;
+use std::slice;
+
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::engine::Engine;
+use crate::hashlife::HashLife;
+use crate::pattern::{parse_pattern, PatternFormat};
+use crate::random;
+use crate::rule::Rule;
+use crate::topology::Topology;
+use crate::universe::Universe;
+
+/// A universe for JavaScript, which sees it as the `Universe` class. It lets
+/// other front ends drive the simulation without the built-in `Ui`.
+#[wasm_bindgen(js_name = Universe)]
+pub struct JsUniverse {
+    universe: Universe,
+}
+
+#[wasm_bindgen(js_class = Universe)]
+impl JsUniverse {
+    /// `new Universe(width, height, rule, topology)`, where the rule is a
+    /// string such as "B3/S23", and the rule and topology are optional.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32, rule: Option<String>, topology: Option<Topology>) -> Result<JsUniverse, JsError> {
+        let rule = match rule {
+            Some(rule) => Rule::parse(&rule)?,
+            None => Rule::conway(),
+        };
+        let universe = Universe::with_rule_and_topology(width, height, rule, topology.unwrap_or_default());
+        Ok(JsUniverse { universe })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.universe.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.universe.height()
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.universe.generation() as f64
+    }
+
+    pub fn population(&self) -> f64 {
+        self.universe.population() as f64
+    }
+
+    pub fn rule(&self) -> String {
+        self.universe.rule().to_string()
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsError> {
+        self.universe.set_rule(Rule::parse(rule)?);
+        Ok(())
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.universe.topology()
+    }
+
+    pub fn tick(&mut self) {
+        self.universe.tick();
+    }
+
+    /// Advances the given number of generations.
+    pub fn step(&mut self, generations: u32) {
+        for _ in 0..generations {
+            self.universe.tick();
+        }
+    }
+
+    pub fn get_cell(&self, row: u32, column: u32) -> bool {
+        self.universe.cell_at(row, column)
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
+        self.universe.set_cell(row, column, alive);
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        self.universe.toggle_cell(row, column);
+    }
+
+    pub fn clear(&mut self) {
+        self.universe.clear();
+    }
+
+    /// Fills the universe with a random soup, at 50% density unless told
+    /// otherwise, and returns the seed so it can be recreated.
+    pub fn randomize(&mut self, seed: Option<u64>, density: Option<f64>) -> u64 {
+        let seed = seed.unwrap_or_else(random::new_seed);
+        self.universe.randomize_with(seed, density.unwrap_or(0.5), None);
+        seed
+    }
+
+    /// Places a pattern file's live cells with its top-left corner at (row,
+    /// column). The format is detected from the text.
+    pub fn load_pattern(&mut self, text: &str, row: u32, column: u32) -> Result<(), JsError> {
+        let pattern = parse_pattern(text)?;
+        self.universe.load_pattern(&pattern, row, column);
+        Ok(())
+    }
+
+    /// The live cells as a pattern file, RLE unless another format is given.
+    pub fn save_pattern(&self, format: Option<PatternFormat>) -> String {
+        format.unwrap_or(PatternFormat::Rle).write(&self.universe.to_pattern())
+    }
+
+    /// The cells, 8 to a byte in row-major order: cell `i` is bit `i % 8` of
+    /// byte `i / 8`. The array is a view of the universe's own memory, so it
+    /// isn't copied, but it's only valid until the universe is next changed
+    /// or WebAssembly memory grows.
+    pub fn cells(&self) -> Uint8Array {
+        // Safe as long as nothing allocates while the view is in use, which
+        // is what the documentation asks of callers
+        unsafe { Uint8Array::view(self.cell_bytes()) }
+    }
+
+    /// Where the bytes behind `cells()` are in WebAssembly memory, for
+    /// building a view of it from JavaScript.
+    pub fn cells_ptr(&self) -> *const u8 {
+        self.cell_bytes().as_ptr()
+    }
+
+    pub fn cells_len(&self) -> usize {
+        self.cell_bytes().len()
+    }
+}
+
+impl JsUniverse {
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    pub fn universe_mut(&mut self) -> &mut Universe {
+        &mut self.universe
+    }
+
+    fn cell_bytes(&self) -> &[u8] {
+        let words = self.universe.cell_words();
+        let len = (self.universe.width() as usize * self.universe.height() as usize).div_ceil(8);
+        debug_assert!(len <= mem::size_of_val(words));
+
+        // The words are little-endian in WebAssembly, so their bytes hold
+        // the cells in order
+        unsafe { slice::from_raw_parts(words.as_ptr() as *const u8, len) }
+    }
+}
+
+impl From<Universe> for JsUniverse {
+    fn from(universe: Universe) -> JsUniverse {
+        JsUniverse { universe }
+    }
+}
+
+/// An unbounded universe stepped with HashLife, which JavaScript sees as the
+/// `HashLife` class. It can run methuselahs and other long-lived patterns for
+/// millions of generations; coordinates are signed and centred on (0, 0).
+#[wasm_bindgen(js_name = HashLife)]
+pub struct JsHashLife {
+    hashlife: HashLife,
+}
+
+#[wasm_bindgen(js_class = HashLife)]
+impl JsHashLife {
+    /// `new HashLife(rule)`, where the rule is an optional string such as
+    /// "B3/S23". Rules where cells are born with no neighbours are refused.
+    #[wasm_bindgen(constructor)]
+    pub fn new(rule: Option<String>) -> Result<JsHashLife, JsError> {
+        let rule = match rule {
+            Some(rule) => Rule::parse(&rule)?,
+            None => Rule::conway(),
+        };
+        Ok(JsHashLife { hashlife: HashLife::new(rule)? })
+    }
+
+    pub fn rule(&self) -> String {
+        self.hashlife.rule().to_string()
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.hashlife.generation() as f64
+    }
+
+    pub fn population(&self) -> f64 {
+        Engine::population(&self.hashlife) as f64
+    }
+
+    /// Advances the given number of generations, a power of two at a time.
+    pub fn step(&mut self, generations: f64) -> Result<(), JsError> {
+        self.hashlife.try_step(generations as u64)?;
+        Ok(())
+    }
+
+    /// Advances 2^k generations in one step.
+    pub fn step_power_of_two(&mut self, k: u8) -> Result<(), JsError> {
+        self.hashlife.step_power_of_two(k)?;
+        Ok(())
+    }
+
+    pub fn get_cell(&self, row: f64, column: f64) -> bool {
+        self.hashlife.cell_at(row as i64, column as i64)
+    }
+
+    pub fn set_cell(&mut self, row: f64, column: f64, alive: bool) {
+        self.hashlife.set_cell(row as i64, column as i64, alive);
+    }
+
+    /// Places a pattern file's live cells with its top-left corner at (row,
+    /// column). The format is detected from the text.
+    pub fn load_pattern(&mut self, text: &str, row: f64, column: f64) -> Result<(), JsError> {
+        let pattern = parse_pattern(text)?;
+        self.hashlife.load_pattern(&pattern, row as i64, column as i64);
+        Ok(())
+    }
+
+    /// The live cells as a pattern file, RLE unless another format is given.
+    /// The pattern starts at its top-left live cell, so it can be loaded
+    /// into a `Universe` to carry on there. Fails if the live cells are too
+    /// far apart for a pattern.
+    pub fn save_pattern(&self, format: Option<PatternFormat>) -> Result<String, JsError> {
+        Ok(format.unwrap_or(PatternFormat::Rle).write(&self.hashlife.to_pattern()?))
+    }
+
+    /// Frees the memory held by results that aren't needed any more.
+    pub fn collect_garbage(&mut self) {
+        self.hashlife.collect_garbage();
+    }
+}
+
+impl JsHashLife {
+    pub fn hashlife(&self) -> &HashLife {
+        &self.hashlife
+    }
+}
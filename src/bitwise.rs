@@ -0,0 +1,185 @@
+//! Computes the next generation 64 cells at a time, using bitwise adders to
+//! count every cell's neighbours in parallel.
+//!
+//! Each row is copied into a "padded" row of 64-bit words with an extra cell
+//! on either side, so bit j holds column j - 1. The padding, and the rows
+//! above and below the grid, are filled in through the topology, which lets
+//! the inner loop ignore edges entirely.
+
+use fixedbitset::{Block, FixedBitSet};
+
+use crate::rule::Rule;
+use crate::topology::Topology;
+
+const BLOCK_BITS: usize = Block::BITS as usize;
+
+pub(crate) fn next_generation(
+    cells: &FixedBitSet,
+    width: u32,
+    height: u32,
+    rule: Rule,
+    topology: Topology,
+) -> FixedBitSet {
+    let (row_count, column_count) = (height as usize, width as usize);
+    let mut next = FixedBitSet::with_capacity(row_count * column_count);
+    if row_count == 0 || column_count == 0 {
+        return next;
+    }
+
+    let births: Vec<u8> = (0..=8).filter(|count| rule.is_birth(*count)).collect();
+    let survivals: Vec<u8> = (0..=8).filter(|count| rule.is_survival(*count)).collect();
+
+    let words = (column_count + 2).div_ceil(64);
+    let padded_row = |row: i64| -> Vec<u64> {
+        let alive = |column: i64| {
+            topology
+                .wrap(row, column, width, height)
+                .is_some_and(|(row, column)| cells.contains(row as usize * column_count + column as usize))
+        };
+
+        let mut padded = vec![0u64; words];
+        if (0..row_count as i64).contains(&row) {
+            let start = row as usize * column_count;
+            padded[0] = read_u64(cells.as_slice(), start) << 1;
+            for (word, value) in padded.iter_mut().enumerate().skip(1) {
+                *value = read_u64(cells.as_slice(), start + word * 64 - 1);
+            }
+            clear_from(&mut padded, column_count + 1);
+        } else {
+            for column in 0..column_count {
+                if alive(column as i64) {
+                    padded[(column + 1) / 64] |= 1 << ((column + 1) % 64);
+                }
+            }
+        }
+
+        if alive(-1) {
+            padded[0] |= 1;
+        }
+        if alive(column_count as i64) {
+            padded[(column_count + 1) / 64] |= 1 << ((column_count + 1) % 64);
+        }
+        padded
+    };
+
+    let mut above = padded_row(-1);
+    let mut middle = padded_row(0);
+    let mut next_row = vec![0u64; words];
+
+    for row in 0..row_count {
+        let below = padded_row(row as i64 + 1);
+
+        for word in 0..words {
+            let mut count = [0u64; 4];
+            for neighbors in [&above, &middle, &below].iter() {
+                let (left, right) = shifted(neighbors, word);
+                add(&mut count, left);
+                add(&mut count, right);
+            }
+            add(&mut count, above[word]);
+            add(&mut count, below[word]);
+
+            next_row[word] = apply_rule(&count, middle[word], &births, &survivals);
+        }
+        clear_from(&mut next_row, column_count + 1);
+
+        // Drop the padding cell on the left and copy the row out
+        let start = row * column_count;
+        for word in 0..column_count.div_ceil(64) {
+            let value = (next_row[word] >> 1) | next_row.get(word + 1).map_or(0, |high| high << 63);
+            let length = (column_count - word * 64).min(64);
+            write_bits(next.as_mut_slice(), start + word * 64, value, length);
+        }
+
+        above = middle;
+        middle = below;
+    }
+
+    next
+}
+
+// The words holding each cell's left and right neighbours.
+fn shifted(row: &[u64], word: usize) -> (u64, u64) {
+    let carry_in = if word > 0 { row[word - 1] >> 63 } else { 0 };
+    let carry_out = row.get(word + 1).map_or(0, |high| high << 63);
+    ((row[word] << 1) | carry_in, (row[word] >> 1) | carry_out)
+}
+
+/// Adds one to the 4-bit neighbour counter of every cell whose bit is set.
+pub(crate) fn add(count: &mut [u64; 4], mut carry: u64) {
+    for bit in count.iter_mut() {
+        let next_carry = *bit & carry;
+        *bit ^= carry;
+        carry = next_carry;
+    }
+}
+
+// The cells whose neighbour counter equals n.
+fn equals(count: &[u64; 4], n: u8) -> u64 {
+    count.iter().enumerate().fold(!0, |mask, (bit, value)| {
+        if n & (1 << bit) != 0 {
+            mask & value
+        } else {
+            mask & !value
+        }
+    })
+}
+
+/// The next state of 64 cells, given their neighbour counters and the
+/// neighbour counts that cause birth and survival.
+pub(crate) fn apply_rule(count: &[u64; 4], alive: u64, births: &[u8], survivals: &[u8]) -> u64 {
+    let born = births.iter().fold(0, |mask, n| mask | equals(count, *n));
+    let survived = survivals.iter().fold(0, |mask, n| mask | equals(count, *n));
+    (!alive & born) | (alive & survived)
+}
+
+fn low_mask(length: usize) -> u64 {
+    if length >= 64 {
+        !0
+    } else {
+        (1 << length) - 1
+    }
+}
+
+fn clear_from(words: &mut [u64], bit: usize) {
+    for (word, value) in words.iter_mut().enumerate() {
+        let start = word * 64;
+        if start >= bit {
+            *value = 0;
+        } else if start + 64 > bit {
+            *value &= low_mask(bit - start);
+        }
+    }
+}
+
+/// Reads the 64 bits starting at any bit position; bits past the end read
+/// as 0.
+pub(crate) fn read_u64(blocks: &[Block], start: usize) -> u64 {
+    let mut value = 0u64;
+    let mut filled = 0;
+    let mut position = start;
+    while filled < 64 {
+        let offset = position % BLOCK_BITS;
+        let block = blocks.get(position / BLOCK_BITS).map_or(0, |block| (block >> offset) as u64);
+        value |= block.checked_shl(filled as u32).unwrap_or(0);
+        filled += BLOCK_BITS - offset;
+        position += BLOCK_BITS - offset;
+    }
+    value
+}
+
+/// ORs the low `length` bits of `value` into the blocks, starting at any bit
+/// position.
+pub(crate) fn write_bits(blocks: &mut [Block], start: usize, value: u64, length: usize) {
+    let mut value = value & low_mask(length);
+    let mut remaining = length;
+    let mut position = start;
+    while remaining > 0 {
+        let offset = position % BLOCK_BITS;
+        let taken = (BLOCK_BITS - offset).min(remaining);
+        blocks[position / BLOCK_BITS] |= ((value & low_mask(taken)) as Block) << offset;
+        value = value.checked_shr(taken as u32).unwrap_or(0);
+        remaining -= taken;
+        position += taken;
+    }
+}
@@ -0,0 +1,118 @@
+use fixedbitset::FixedBitSet;
+
+use crate::rect::Rect;
+
+const BITS: usize = usize::BITS as usize;
+
+/// The (row, column) of each live cell in a universe or a region of one, in
+/// row-major order. Whole words of dead cells are skipped at once, so sparse
+/// universes iterate quickly, though every word of the region is still read.
+#[derive(Clone, Debug)]
+pub struct LiveCells<'a> {
+    blocks: &'a [usize],
+    width: u32,
+    region: Rect,
+    // A region as wide as the universe is one run of bits; otherwise each
+    // row of it is a separate run
+    rows_per_span: u32,
+    next_row: u32,
+    span_start: usize,
+    span_end: usize,
+    block: usize,
+    bits: usize,
+}
+
+impl<'a> LiveCells<'a> {
+    // The live cells of a universe's bits inside a region, clipped to the
+    // universe's edges.
+    pub(crate) fn new(cells: &'a FixedBitSet, width: u32, height: u32, region: Rect) -> LiveCells<'a> {
+        let region = region.clipped(width, height);
+        let rows_per_span = if region.column == 0 && region.width == width {
+            region.height.max(1)
+        } else {
+            1
+        };
+
+        LiveCells {
+            blocks: cells.as_slice(),
+            width,
+            region,
+            rows_per_span,
+            next_row: region.row,
+            span_start: 0,
+            span_end: 0,
+            block: 0,
+            bits: 0,
+        }
+    }
+
+    // A word of cells, with the bits outside the current span cleared.
+    fn load(&self, block: usize) -> usize {
+        let mut bits = self.blocks[block];
+        if block == self.span_start / BITS {
+            bits &= !0 << (self.span_start % BITS);
+        }
+        if block == (self.span_end - 1) / BITS && !self.span_end.is_multiple_of(BITS) {
+            bits &= !(!0 << (self.span_end % BITS));
+        }
+        bits
+    }
+
+    // Moves on to the next run of bits, returning false after the last.
+    fn next_span(&mut self) -> bool {
+        if self.region.is_empty() || self.next_row >= self.region.bottom() {
+            return false;
+        }
+
+        let row = self.next_row as usize;
+        let rows = self.rows_per_span as usize;
+        let width = self.width as usize;
+        self.span_start = row * width + self.region.column as usize;
+        self.span_end = (row + rows - 1) * width + self.region.right() as usize;
+        self.next_row += self.rows_per_span;
+        self.block = self.span_start / BITS;
+        self.bits = self.load(self.block);
+        true
+    }
+}
+
+impl Iterator for LiveCells<'_> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<(u32, u32)> {
+        loop {
+            if self.bits != 0 {
+                let idx = self.block * BITS + self.bits.trailing_zeros() as usize;
+                self.bits &= self.bits - 1;
+                return Some((idx as u32 / self.width, idx as u32 % self.width));
+            }
+
+            if (self.block + 1) * BITS < self.span_end {
+                self.block += 1;
+                self.bits = self.load(self.block);
+            } else if !self.next_span() {
+                return None;
+            }
+        }
+    }
+}
+
+// The smallest rectangle holding every live cell, or None if there are none.
+pub(crate) fn bounding_box(cells: &FixedBitSet, width: u32, height: u32) -> Option<Rect> {
+    let (first, last) = cells.minimum().zip(cells.maximum())?;
+    let (top, bottom) = (first as u32 / width, last as u32 / width);
+
+    // The rows are known, so only the columns need looking for, and the
+    // search can stop once they reach both edges
+    let mut left = first as u32 % width;
+    let mut right = last as u32 % width;
+    for (_, column) in LiveCells::new(cells, width, height, Rect::new(top, 0, width, bottom - top + 1)) {
+        left = left.min(column);
+        right = right.max(column);
+        if left == 0 && right == width - 1 {
+            break;
+        }
+    }
+
+    Some(Rect::new(top, left, right - left + 1, bottom - top + 1))
+}
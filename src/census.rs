@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use crate::library::library_pattern;
+use crate::rect::Rect;
+use crate::rule::Rule;
+use crate::sparse::SparseUniverse;
+
+// The built-in patterns the census recognises: still lifes, oscillators and
+// spaceships, which keep their shape (up to phase) as they run.
+const KNOWN_OBJECTS: &[&str] = &[
+    "block",
+    "beehive",
+    "loaf",
+    "boat",
+    "tub",
+    "ship",
+    "pond",
+    "barge",
+    "long-boat",
+    "blinker",
+    "toad",
+    "beacon",
+    "pulsar",
+    "pentadecathlon",
+    "glider",
+    "lwss",
+    "mwss",
+    "hwss",
+];
+
+type Orientation = fn((i64, i64)) -> (i64, i64);
+
+// More than any known object's period
+const MAX_PERIOD: usize = 30;
+
+/// One connected group of live cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CensusObject {
+    /// The id of the built-in pattern it matches, such as "blinker", or
+    /// None if it isn't one of them.
+    pub name: Option<&'static str>,
+    /// Where the object is. On a torus, an object that wraps around the edge
+    /// has its box starting on the far side.
+    pub bounding_box: Rect,
+    pub population: usize,
+}
+
+/// The objects a universe is made of.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    objects: Vec<CensusObject>,
+}
+
+impl Census {
+    pub(crate) fn new(objects: Vec<CensusObject>) -> Census {
+        Census { objects }
+    }
+
+    /// Every object, in the order their first live cells appear, row by row.
+    pub fn objects(&self) -> &[CensusObject] {
+        &self.objects
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// How many of each object there are, with unrecognised ones counted
+    /// under "unknown".
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for object in self.objects.iter() {
+            *counts.entry(object.name.unwrap_or("unknown")).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.objects.iter().filter(|object| object.name == Some(name)).count()
+    }
+}
+
+/// The name of the known object with these live cells, in any phase,
+/// rotation or reflection. Objects are only known under B3/S23.
+pub(crate) fn identify(cells: &[(i64, i64)], rule: Rule) -> Option<&'static str> {
+    if rule != Rule::conway() {
+        return None;
+    }
+
+    static KNOWN: OnceLock<HashMap<Vec<(i64, i64)>, &'static str>> = OnceLock::new();
+
+    let known = KNOWN.get_or_init(|| {
+        let mut known = HashMap::new();
+        for name in KNOWN_OBJECTS.iter() {
+            let pattern = library_pattern(name).expect("Known objects are in the library");
+            let mut universe = SparseUniverse::from_pattern(&pattern).expect("Known objects have B3/S23 rules");
+
+            let first = canonical(&universe.live_cells().collect::<Vec<_>>());
+            let mut phase = first.clone();
+            for _ in 0..MAX_PERIOD {
+                known.insert(phase, *name);
+                universe.tick();
+                phase = canonical(&universe.live_cells().collect::<Vec<_>>());
+                if phase == first {
+                    break;
+                }
+            }
+        }
+        known
+    });
+
+    known.get(&canonical(cells)).cloned()
+}
+
+// The same shape for cells that only differ by position, rotation or
+// reflection: the smallest of the eight orientations' sorted cells, each
+// moved so its top-left corner is (0, 0).
+fn canonical(cells: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let orientations: [Orientation; 8] = [
+        |(row, column)| (row, column),
+        |(row, column)| (column, -row),
+        |(row, column)| (-row, -column),
+        |(row, column)| (-column, row),
+        |(row, column)| (row, -column),
+        |(row, column)| (column, row),
+        |(row, column)| (-row, column),
+        |(row, column)| (-column, -row),
+    ];
+
+    orientations
+        .iter()
+        .map(|orient| {
+            let mut oriented: Vec<(i64, i64)> = cells.iter().map(|cell| orient(*cell)).collect();
+            let top = oriented.iter().map(|(row, _)| *row).min().unwrap_or(0);
+            let left = oriented.iter().map(|(_, column)| *column).min().unwrap_or(0);
+            for (row, column) in oriented.iter_mut() {
+                *row -= top;
+                *column -= left;
+            }
+            oriented.sort_unstable();
+            oriented
+        })
+        .min()
+        .unwrap_or_default()
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use fixedbitset::FixedBitSet;
+
+use crate::rect::Rect;
+
+/// The cells that were born and the cells that died between two states of a
+/// universe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeSet {
+    width: u32,
+    born: FixedBitSet,
+    died: FixedBitSet,
+}
+
+impl ChangeSet {
+    pub(crate) fn between(width: u32, before: &FixedBitSet, after: &FixedBitSet) -> ChangeSet {
+        let mut born = after.clone();
+        born.difference_with(before);
+
+        let mut died = before.clone();
+        died.difference_with(after);
+
+        ChangeSet { width, born, died }
+    }
+
+    /// Folds in the changes that happened after these, leaving the net
+    /// changes across both: a cell that was born and then died is unchanged.
+    pub fn merge(&mut self, later: &ChangeSet) {
+        let mut born = self.born.clone();
+        born.difference_with(&later.died);
+        let mut later_born = later.born.clone();
+        later_born.difference_with(&self.died);
+        born.union_with(&later_born);
+
+        let mut died = self.died.clone();
+        died.difference_with(&later.born);
+        let mut later_died = later.died.clone();
+        later_died.difference_with(&self.born);
+        died.union_with(&later_died);
+
+        self.born = born;
+        self.died = died;
+    }
+
+    /// The cells that came to life, as (row, column) pairs in row-major order.
+    pub fn births(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.born.ones().map(move |idx| self.coordinates(idx))
+    }
+
+    /// The cells that died, as (row, column) pairs in row-major order.
+    pub fn deaths(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.died.ones().map(move |idx| self.coordinates(idx))
+    }
+
+    pub fn birth_count(&self) -> usize {
+        self.born.count_ones(..)
+    }
+
+    pub fn death_count(&self) -> usize {
+        self.died.count_ones(..)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.born.is_clear() && self.died.is_clear()
+    }
+
+    /// Rectangles that together cover every changed cell and nothing else.
+    /// Runs of changed cells in a row become one rectangle, and identical
+    /// runs in consecutive rows are merged.
+    pub fn dirty_rects(&self) -> Vec<Rect> {
+        let mut changed = self.born.clone();
+        changed.union_with(&self.died);
+
+        let mut rects: Vec<Rect> = Vec::new();
+        // Rectangles that reached the previous row, by (column, width)
+        let mut open: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut current_row = None;
+        let mut row_runs: Vec<(u32, u32)> = Vec::new();
+
+        let mut close_row = |row: u32, runs: &mut Vec<(u32, u32)>, open: &mut HashMap<(u32, u32), usize>| {
+            let mut still_open = HashMap::new();
+            for (column, width) in runs.drain(..) {
+                let index = match open.get(&(column, width)) {
+                    Some(index) if rects[*index].bottom() == row => {
+                        rects[*index].height += 1;
+                        *index
+                    }
+                    _ => {
+                        rects.push(Rect::new(row, column, width, 1));
+                        rects.len() - 1
+                    }
+                };
+                still_open.insert((column, width), index);
+            }
+            *open = still_open;
+        };
+
+        for (row, column) in changed.ones().map(|idx| self.coordinates(idx)) {
+            if current_row != Some(row) {
+                if let Some(previous) = current_row {
+                    close_row(previous, &mut row_runs, &mut open);
+                }
+                current_row = Some(row);
+            }
+
+            match row_runs.last_mut() {
+                Some((start, width)) if *start + *width == column => *width += 1,
+                _ => row_runs.push((column, 1)),
+            }
+        }
+        if let Some(row) = current_row {
+            close_row(row, &mut row_runs, &mut open);
+        }
+
+        rects
+    }
+
+    fn coordinates(&self, idx: usize) -> (u32, u32) {
+        (idx as u32 / self.width, idx as u32 % self.width)
+    }
+}
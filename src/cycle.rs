@@ -0,0 +1,185 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Whether a simulation has settled down, and if so, into what.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stability {
+    /// No generation has repeated yet.
+    Evolving,
+    /// Every generation since `since` has been the same, including when
+    /// everything has died.
+    Static { since: i64 },
+    /// The generations since `since` repeat every `period` generations.
+    Oscillating { period: u64, since: i64 },
+    /// The generations since `since` repeat every `period` generations,
+    /// moved by `dx` columns and `dy` rows, like a spaceship.
+    Translating { period: u64, dx: i64, dy: i64, since: i64 },
+}
+
+impl Stability {
+    pub fn is_settled(&self) -> bool {
+        *self != Stability::Evolving
+    }
+
+    /// How many generations it takes to repeat, or None while evolving.
+    pub fn period(&self) -> Option<u64> {
+        match self {
+            Stability::Evolving => None,
+            Stability::Static { .. } => Some(1),
+            Stability::Oscillating { period, .. } | Stability::Translating { period, .. } => Some(*period),
+        }
+    }
+}
+
+impl fmt::Display for Stability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stability::Evolving => write!(f, "Evolving"),
+            Stability::Static { since } => write!(f, "Static since generation {}", since),
+            Stability::Oscillating { period, since } => {
+                write!(f, "Period {} oscillator since generation {}", period, since)
+            }
+            Stability::Translating { period, dx, dy, since } => write!(
+                f,
+                "Period {} spaceship moving ({}, {}) since generation {}",
+                period, dx, dy, since
+            ),
+        }
+    }
+}
+
+/// Decides when a running simulation should pause because it has settled.
+/// It asks once per settling, so after a pause, playing on keeps going until
+/// an edit starts the search over and the universe settles again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AutoPause {
+    paused_at: Option<i64>,
+}
+
+impl AutoPause {
+    pub fn new() -> AutoPause {
+        AutoPause::default()
+    }
+
+    /// Whether to pause now, given the universe's current stability and
+    /// generation.
+    pub fn should_pause(&mut self, stability: Option<Stability>, generation: i64) -> bool {
+        if !stability.is_some_and(|stability| stability.is_settled()) {
+            self.paused_at = None;
+            return false;
+        }
+        if self.paused_at.is_some() {
+            return false;
+        }
+        self.paused_at = Some(generation);
+        true
+    }
+
+    /// The generation it last paused at, if the universe is still settled.
+    pub fn paused_at(&self) -> Option<i64> {
+        self.paused_at
+    }
+}
+
+// Spots repeated generations by hashing each one's live cells relative to
+// their bounding box, remembering where and when each shape was last seen.
+// Only the most recent `window` generations are remembered, so memory stays
+// bounded and periods longer than the window go unnoticed. Two different
+// shapes with the same 64-bit hash would be mistaken for a repeat, which is
+// unlikely enough to ignore.
+#[derive(Clone, Debug)]
+pub(crate) struct CycleDetector {
+    seen: HashMap<u64, (i64, i64, i64)>,
+    // The shapes in `seen`, oldest first, with the generation of each
+    order: VecDeque<(i64, u64)>,
+    window: usize,
+    stability: Stability,
+}
+
+impl CycleDetector {
+    pub(crate) fn new(window: usize) -> CycleDetector {
+        CycleDetector {
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            window: window.max(1),
+            stability: Stability::Evolving,
+        }
+    }
+
+    pub(crate) fn stability(&self) -> Stability {
+        self.stability
+    }
+
+    // Whether a repeat has been found, after which there's nothing more to
+    // observe until a reset.
+    pub(crate) fn is_settled(&self) -> bool {
+        self.stability.is_settled()
+    }
+
+    // Whether nothing has been observed since it was created or reset.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.order.is_empty() && !self.is_settled()
+    }
+
+    pub(crate) fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        self.evict();
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+        self.stability = Stability::Evolving;
+    }
+
+    // Forgets the oldest shapes until no more than the window are left.
+    fn evict(&mut self) {
+        while self.order.len() > self.window {
+            if let Some((generation, shape)) = self.order.pop_front() {
+                if self.seen.get(&shape).is_some_and(|(since, _, _)| *since == generation) {
+                    self.seen.remove(&shape);
+                }
+            }
+        }
+    }
+
+    // Records a generation, given its live cells as (row, column) pairs in
+    // row-major order. Once settled, nothing more is recorded until a reset.
+    pub(crate) fn observe(&mut self, generation: i64, live_cells: &[(i64, i64)]) -> Stability {
+        if self.stability.is_settled() {
+            return self.stability;
+        }
+
+        let top = live_cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+        let left = live_cells.iter().map(|(_, column)| *column).min().unwrap_or(0);
+
+        let mut hasher = DefaultHasher::new();
+        for (row, column) in live_cells {
+            (row - top, column - left).hash(&mut hasher);
+        }
+        let shape = hasher.finish();
+
+        match self.seen.insert(shape, (generation, top, left)) {
+            Some((since, previous_top, previous_left)) => {
+                let period = (generation - since) as u64;
+                let (dx, dy) = (left - previous_left, top - previous_top);
+                self.stability = match (period, dx, dy) {
+                    (1, 0, 0) => Stability::Static { since },
+                    (_, 0, 0) => Stability::Oscillating { period, since },
+                    _ => Stability::Translating { period, dx, dy, since },
+                };
+                // Nothing more will be looked up until a reset
+                self.seen.clear();
+                self.order.clear();
+            }
+            None => {
+                self.order.push_back((generation, shape));
+                self.evict();
+            }
+        }
+
+        self.stability
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+/// Why an engine couldn't do what was asked of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    /// The rule gives birth to cells with no live neighbours, which would
+    /// fill an unbounded universe in a single generation.
+    UnsupportedRule(Rule),
+    /// Stepping would take live cells or the generation count further than
+    /// the engine's coordinates and counters can reach.
+    OutOfRange,
+    /// The live cells are too far apart for a `Pattern`, whose rows and
+    /// columns are counted in u32s.
+    TooLarge,
+}
+
+/// The operations shared by every way of running a simulation, so that code
+/// driving a simulation doesn't need to know how it's stored or stepped.
+pub trait Engine {
+    fn rule(&self) -> Rule;
+
+    fn generation(&self) -> i64;
+
+    fn population(&self) -> u64;
+
+    /// Advances the simulation by one generation.
+    fn tick(&mut self);
+
+    /// Advances the simulation by many generations. Engines that can skip
+    /// ahead should override this; the default ticks once per generation.
+    /// Engines with a limited range stop once they reach it.
+    fn step(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.tick();
+        }
+    }
+
+    /// The smallest region containing every live cell.
+    fn to_pattern(&self) -> Result<Pattern, EngineError>;
+}
+
+/// Reading and editing single cells, on top of `Engine`, so a front end can
+/// show and change any part of a simulation without knowing how it's stored.
+/// Coordinates are signed, as unbounded universes stretch in every direction;
+/// bounded ones map them onto their own cells the way their topology does.
+pub trait Grid: Engine {
+    /// The width and height the cells are confined to, or None if the
+    /// universe is unbounded.
+    fn size(&self) -> Option<(u32, u32)>;
+
+    fn cell_at(&self, row: i64, column: i64) -> bool;
+
+    fn set_cell(&mut self, row: i64, column: i64, alive: bool);
+
+    fn toggle_cell(&mut self, row: i64, column: i64) {
+        let alive = self.cell_at(row, column);
+        self.set_cell(row, column, !alive);
+    }
+
+    /// The live cells in the `height` rows and `width` columns from (top,
+    /// left), such as the part of the universe a renderer is showing, in no
+    /// particular order.
+    fn live_cells_in(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<(i64, i64)>;
+
+    /// The same region as text, one string a row and one character a cell:
+    /// ◼ for live cells and ◻ for dead ones. This is how `Universe` displays
+    /// itself and how `life-tui` draws its view.
+    fn text_rows(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<String> {
+        let live: HashSet<(i64, i64)> = self.live_cells_in(top, left, height, width).into_iter().collect();
+        (top..top + height as i64)
+            .map(|row| {
+                (left..left + width as i64)
+                    .map(|column| if live.contains(&(row, column)) { '◼' } else { '◻' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Kills every cell and starts counting generations from 0 again.
+    fn clear(&mut self);
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EngineError::UnsupportedRule(rule) => write!(
+                f,
+                "rule {} gives birth to cells with no live neighbours, which would fill an unbounded universe",
+                rule
+            ),
+            EngineError::OutOfRange => write!(f, "the simulation would grow past the range the engine can address"),
+            EngineError::TooLarge => write!(f, "the live cells are too far apart to save as a pattern"),
+        }
+    }
+}
+
+impl Error for EngineError {}
@@ -0,0 +1,465 @@
+//! Bill Gosper's HashLife algorithm, which stores an unbounded universe as a
+//! quadtree of shared, immutable nodes and memoizes each node's future. For
+//! patterns with a lot of repetition in space or time, this lets it advance
+//! 2^k generations in roughly the time it takes to advance one.
+
+use std::collections::HashMap;
+
+use crate::engine::{Engine, EngineError};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+// Once this many nodes exist, unreachable nodes and memoized results are
+// thrown away before the next step.
+const GARBAGE_COLLECTION_THRESHOLD: usize = 1 << 22;
+
+// The root never grows past this level, so every coordinate inside it, and
+// every sum of two of them, fits in an i64.
+const MAX_LEVEL: u8 = 62;
+
+/// The largest k that `HashLife::step_power_of_two` accepts. Stepping 2^k
+/// generations needs a root of at least level k + 3.
+pub const MAX_STEP_POWER: u8 = MAX_LEVEL - 3;
+
+struct Node {
+    // nw, ne, sw, se; unused for single cells
+    children: [NodeId; 4],
+    level: u8,
+    population: u64,
+}
+
+/// An unbounded universe stepped with HashLife. Cells are addressed by signed
+/// (row, column) coordinates, from -2^61 up to but not including 2^61.
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    empty: Vec<NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    root: NodeId,
+    generation: i64,
+}
+
+impl HashLife {
+    /// Fails if the rule gives birth to cells with no live neighbours, as
+    /// that would fill the infinite plane.
+    pub fn new(rule: Rule) -> Result<HashLife, EngineError> {
+        if rule.is_birth(0) {
+            return Err(EngineError::UnsupportedRule(rule));
+        }
+
+        let mut hashlife = HashLife {
+            rule,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            empty: Vec::new(),
+            results: HashMap::new(),
+            root: DEAD,
+            generation: 0,
+        };
+        hashlife.reset_nodes();
+        Ok(hashlife)
+    }
+
+    /// Builds a universe from a pattern, using the pattern's rule or B3/S23
+    /// if it has none. The pattern's top-left corner is placed at (0, 0).
+    pub fn from_pattern(pattern: &Pattern) -> Result<HashLife, EngineError> {
+        let mut hashlife = HashLife::new(pattern.rule.unwrap_or_default())?;
+        hashlife.load_pattern(pattern, 0, 0);
+        Ok(hashlife)
+    }
+
+    pub fn cell_at(&self, row: i64, column: i64) -> bool {
+        let mut node = self.root;
+        let mut level = self.nodes[node as usize].level;
+        if !self.contains(level, row, column) {
+            return false;
+        }
+
+        // Coordinates relative to the current node's top-left corner
+        let half = 1i64 << (level - 1);
+        let mut row = row + half;
+        let mut column = column + half;
+        while level > 0 {
+            let half = 1i64 << (level - 1);
+            let quadrant = (row >= half) as usize * 2 + (column >= half) as usize;
+            node = self.nodes[node as usize].children[quadrant];
+            row %= half;
+            column %= half;
+            level -= 1;
+        }
+        node == ALIVE
+    }
+
+    /// Cells outside the addressable range are always dead, so setting them
+    /// does nothing.
+    pub fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        if !self.contains(MAX_LEVEL, row, column) {
+            return;
+        }
+        while !self.contains(self.level(self.root), row, column) {
+            self.root = self.expand(self.root);
+        }
+
+        let half = 1i64 << (self.level(self.root) - 1);
+        self.root = self.set_in(self.root, row + half, column + half, alive);
+    }
+
+    pub fn toggle_cell(&mut self, row: i64, column: i64) {
+        let alive = self.cell_at(row, column);
+        self.set_cell(row, column, !alive);
+    }
+
+    /// Places a pattern's live cells with its top-left corner at (row, column).
+    pub fn load_pattern(&mut self, pattern: &Pattern, row: i64, column: i64) {
+        for (pattern_row, pattern_column) in pattern.live_cells() {
+            self.set_cell(row.saturating_add(pattern_row as i64), column.saturating_add(pattern_column as i64), true);
+        }
+    }
+
+    /// Advances the universe by 2^k generations in one step.
+    ///
+    /// Fails, leaving the universe as it was, if k is more than
+    /// `MAX_STEP_POWER`, if the pattern could grow out of the addressable
+    /// range, or if the generation count would overflow.
+    pub fn step_power_of_two(&mut self, k: u8) -> Result<(), EngineError> {
+        if k > MAX_STEP_POWER {
+            return Err(EngineError::OutOfRange);
+        }
+        let generation = self.generation.checked_add(1 << k).ok_or(EngineError::OutOfRange)?;
+
+        if self.nodes.len() > GARBAGE_COLLECTION_THRESHOLD {
+            self.collect_garbage();
+        }
+
+        // The result of a step is the centre half of the root, so the root
+        // has to be big enough for the pattern to grow by 2^k cells in every
+        // direction without leaving it. Once the pattern fits in the centre
+        // half of a root of level k + 2 or more, one more expansion leaves a
+        // margin of at least 2^k. Expanding doesn't change any cells, so
+        // giving up part way leaves the universe as it was.
+        let mut root = self.root;
+        while self.level(root) < k + 2 || !self.is_padded(root) {
+            if self.level(root) >= MAX_LEVEL - 1 {
+                return Err(EngineError::OutOfRange);
+            }
+            root = self.expand(root);
+        }
+        if self.level(root) >= MAX_LEVEL {
+            return Err(EngineError::OutOfRange);
+        }
+        root = self.expand(root);
+
+        self.root = self.successor(root, k);
+        self.generation = generation;
+        Ok(())
+    }
+
+    /// Advances the universe by any number of generations, a power of two at
+    /// a time. If that fails, the generations stepped so far are kept.
+    pub fn try_step(&mut self, generations: u64) -> Result<(), EngineError> {
+        for k in 0..64 {
+            if generations & (1u64 << k) != 0 {
+                self.step_power_of_two(k)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets every memoized result and every node that isn't part of the
+    /// current universe.
+    pub fn collect_garbage(&mut self) {
+        let old_nodes = std::mem::take(&mut self.nodes);
+        self.reset_nodes();
+
+        let mut copied = HashMap::new();
+        self.root = self.copy_from(&old_nodes, self.root, &mut copied);
+    }
+
+    fn reset_nodes(&mut self) {
+        self.nodes.clear();
+        self.index.clear();
+        self.results.clear();
+        self.empty.clear();
+
+        self.nodes.push(Node { children: [DEAD; 4], level: 0, population: 0 });
+        self.nodes.push(Node { children: [DEAD; 4], level: 0, population: 1 });
+        self.empty.push(DEAD);
+    }
+
+    fn copy_from(&mut self, old_nodes: &[Node], id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+        if let Some(new_id) = copied.get(&id) {
+            return *new_id;
+        }
+
+        let children = old_nodes[id as usize].children;
+        let new_children = [
+            self.copy_from(old_nodes, children[0], copied),
+            self.copy_from(old_nodes, children[1], copied),
+            self.copy_from(old_nodes, children[2], copied),
+            self.copy_from(old_nodes, children[3], copied),
+        ];
+        let new_id = self.join(new_children);
+        copied.insert(id, new_id);
+        new_id
+    }
+
+    fn level(&self, id: NodeId) -> u8 {
+        self.nodes[id as usize].level
+    }
+
+    fn children(&self, id: NodeId) -> [NodeId; 4] {
+        self.nodes[id as usize].children
+    }
+
+    fn contains(&self, level: u8, row: i64, column: i64) -> bool {
+        if level == 0 {
+            return false;
+        }
+        let half = 1i64 << (level - 1);
+        (-half..half).contains(&row) && (-half..half).contains(&column)
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(id) = self.index.get(&children) {
+            return *id;
+        }
+
+        let level = self.level(children[0]) + 1;
+        let population = children.iter().map(|child| self.nodes[*child as usize].population).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node { children, level, population });
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = *self.empty.last().unwrap();
+            let node = self.join([child; 4]);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+
+    // Surrounds a node with empty space, keeping it centred.
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let level = self.level(id);
+        if level == 0 {
+            // Start with a 2×2 root so that coordinates have a centre
+            let empty = self.empty_node(0);
+            return self.join([empty, empty, empty, id]);
+        }
+
+        let empty = self.empty_node(level - 1);
+        let [nw, ne, sw, se] = self.children(id);
+        let nw = self.join([empty, empty, empty, nw]);
+        let ne = self.join([empty, empty, ne, empty]);
+        let sw = self.join([empty, sw, empty, empty]);
+        let se = self.join([se, empty, empty, empty]);
+        self.join([nw, ne, sw, se])
+    }
+
+    // Whether every live cell lies within the centre half of the node.
+    fn is_padded(&self, id: NodeId) -> bool {
+        if self.level(id) < 2 {
+            return self.nodes[id as usize].population == 0;
+        }
+
+        let [nw, ne, sw, se] = self.children(id);
+        let inner = [
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ];
+        let inner_population: u64 = inner.iter().map(|node| self.nodes[*node as usize].population).sum();
+        inner_population == self.nodes[id as usize].population
+    }
+
+    // Sets a cell given coordinates relative to the node's top-left corner.
+    fn set_in(&mut self, id: NodeId, row: i64, column: i64, alive: bool) -> NodeId {
+        let level = self.level(id);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+
+        let half = 1i64 << (level - 1);
+        let quadrant = (row >= half) as usize * 2 + (column >= half) as usize;
+        let mut children = self.children(id);
+        children[quadrant] = self.set_in(children[quadrant], row % half, column % half, alive);
+        self.join(children)
+    }
+
+    // The centre half of a node, 2^k generations in the future. Requires
+    // k <= level - 2.
+    fn successor(&mut self, id: NodeId, k: u8) -> NodeId {
+        let level = self.level(id);
+        if self.nodes[id as usize].population == 0 {
+            return self.empty_node(level - 1);
+        }
+        if let Some(result) = self.results.get(&(id, k)) {
+            return *result;
+        }
+
+        let result = if level == 2 {
+            self.step_leaf(id)
+        } else {
+            // The nine overlapping nodes, a quarter of this one's size, that
+            // tile its centre
+            let [nw, ne, sw, se] = self.children(id);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+
+            let n00 = nw;
+            let n01 = self.join([nw_ne, ne_nw, nw_se, ne_sw]);
+            let n02 = ne;
+            let n10 = self.join([nw_sw, nw_se, sw_nw, sw_ne]);
+            let n11 = self.join([nw_se, ne_sw, sw_ne, se_nw]);
+            let n12 = self.join([ne_sw, ne_se, se_nw, se_ne]);
+            let n20 = sw;
+            let n21 = self.join([sw_ne, se_nw, sw_se, se_sw]);
+            let n22 = se;
+
+            // At full speed both halves of the step advance time; otherwise
+            // the first half just recentres
+            let full_speed = k == level - 2;
+            let first = |hashlife: &mut HashLife, node: NodeId| {
+                if full_speed {
+                    hashlife.successor(node, k - 1)
+                } else {
+                    hashlife.center(node)
+                }
+            };
+
+            let r00 = first(self, n00);
+            let r01 = first(self, n01);
+            let r02 = first(self, n02);
+            let r10 = first(self, n10);
+            let r11 = first(self, n11);
+            let r12 = first(self, n12);
+            let r20 = first(self, n20);
+            let r21 = first(self, n21);
+            let r22 = first(self, n22);
+
+            let second_k = if full_speed { k - 1 } else { k };
+            let nw = self.join([r00, r01, r10, r11]);
+            let ne = self.join([r01, r02, r11, r12]);
+            let sw = self.join([r10, r11, r20, r21]);
+            let se = self.join([r11, r12, r21, r22]);
+
+            let nw = self.successor(nw, second_k);
+            let ne = self.successor(ne, second_k);
+            let sw = self.successor(sw, second_k);
+            let se = self.successor(se, second_k);
+            self.join([nw, ne, sw, se])
+        };
+
+        self.results.insert((id, k), result);
+        result
+    }
+
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(id);
+        let inner = [
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ];
+        self.join(inner)
+    }
+
+    // Runs the rule on a 4×4 node, giving its centre 2×2 one generation on.
+    fn step_leaf(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, child) in self.children(id).iter().enumerate() {
+            for (cell, grandchild) in self.children(*child).iter().enumerate() {
+                let row = (quadrant / 2) * 2 + cell / 2;
+                let column = (quadrant % 2) * 2 + cell % 2;
+                cells[row][column] = *grandchild == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (cell, state) in next.iter_mut().enumerate() {
+            let row = 1 + cell / 2;
+            let column = 1 + cell % 2;
+            let live_neighbors = cells[row - 1..=row + 1]
+                .iter()
+                .flat_map(|neighbors| neighbors[column - 1..=column + 1].iter())
+                .filter(|alive| **alive)
+                .count() as u8
+                - cells[row][column] as u8;
+            if self.rule.next_state(cells[row][column], live_neighbors) {
+                *state = ALIVE;
+            }
+        }
+
+        self.join(next)
+    }
+
+    fn collect_cells(&self, id: NodeId, top: i64, left: i64, cells: &mut Vec<(i64, i64)>) {
+        let node = &self.nodes[id as usize];
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((top, left));
+            return;
+        }
+
+        let half = 1i64 << (node.level - 1);
+        self.collect_cells(node.children[0], top, left, cells);
+        self.collect_cells(node.children[1], top, left + half, cells);
+        self.collect_cells(node.children[2], top + half, left, cells);
+        self.collect_cells(node.children[3], top + half, left + half, cells);
+    }
+}
+
+impl Engine for HashLife {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn generation(&self) -> i64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    // Stops at the edge of the addressable range; use step_power_of_two()
+    // or try_step() to find out when that happens
+    fn tick(&mut self) {
+        let _ = self.step_power_of_two(0);
+    }
+
+    fn step(&mut self, generations: u64) {
+        let _ = self.try_step(generations);
+    }
+
+    fn to_pattern(&self) -> Result<Pattern, EngineError> {
+        let mut cells = Vec::new();
+        let half = match self.level(self.root) {
+            0 => 0,
+            level => 1i64 << (level - 1),
+        };
+        self.collect_cells(self.root, -half, -half, &mut cells);
+
+        let mut pattern = Pattern::from_signed_cells(&cells, 0).map_err(|_| EngineError::TooLarge)?;
+        pattern.rule = Some(self.rule);
+        Ok(pattern)
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+use std::mem;
+
+use fixedbitset::FixedBitSet;
+
+/// What replaced a state in a universe's history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HistoryKind {
+    /// The simulation advanced a generation.
+    Tick,
+    /// Cells were changed by hand, such as a toggle, clear or randomize.
+    Edit,
+}
+
+// A universe's cells and generation at some point, and what happened next.
+#[derive(Clone, Debug)]
+pub(crate) struct Snapshot {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) cells: FixedBitSet,
+    pub(crate) generation: i64,
+    pub(crate) kind: HistoryKind,
+}
+
+// Earlier states that can be undone back to, and undone states that can be
+// redone, holding no more than a memory budget. When recording a new state
+// would go over budget, the oldest states are forgotten.
+#[derive(Clone, Debug)]
+pub(crate) struct History {
+    budget: usize,
+    used: usize,
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl Snapshot {
+    fn size(&self) -> usize {
+        mem::size_of::<Snapshot>() + mem::size_of_val(self.cells.as_slice())
+    }
+}
+
+impl History {
+    pub(crate) fn new(budget: usize) -> History {
+        History {
+            budget,
+            used: 0,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    pub(crate) fn memory_used(&self) -> usize {
+        self.used
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub(crate) fn last_kind(&self) -> Option<HistoryKind> {
+        self.undo.back().map(|snapshot| snapshot.kind)
+    }
+
+    // Remembers the state from before a change. Anything that was undone
+    // can no longer be redone.
+    pub(crate) fn record(&mut self, snapshot: Snapshot) {
+        for undone in self.redo.drain(..) {
+            self.used -= undone.size();
+        }
+
+        self.used += snapshot.size();
+        self.undo.push_back(snapshot);
+        self.trim();
+    }
+
+    // Swaps the current state for the one before the last change, keeping
+    // the current state to redo.
+    pub(crate) fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.used += current.size();
+        self.used -= previous.size();
+        self.redo.push(Snapshot { kind: previous.kind, ..current });
+        self.trim();
+        Some(previous)
+    }
+
+    pub(crate) fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.used += current.size();
+        self.used -= next.size();
+        self.undo.push_back(Snapshot { kind: next.kind, ..current });
+        self.trim();
+        Some(next)
+    }
+
+    fn trim(&mut self) {
+        while self.used > self.budget {
+            let forgotten = match self.undo.pop_front() {
+                Some(snapshot) => snapshot,
+                None => self.redo.remove(0),
+            };
+            self.used -= forgotten.size();
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::universe::Universe;
+
+/// A colour, as red, green and blue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+/// How pictures of a universe look. The default matches the canvas in the
+/// browser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageStyle {
+    /// The width and height of each cell, in pixels.
+    pub cell_size: u32,
+    /// Whether cells are separated by one-pixel lines, as on the canvas.
+    pub grid_lines: bool,
+    pub grid_color: Color,
+    pub dead_color: Color,
+    pub alive_color: Color,
+}
+
+/// Why a picture couldn't be made.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageError {
+    /// The picture would be wider or taller than the format allows, or have
+    /// more pixels than fit in memory. The size is in pixels.
+    TooLarge { width: u64, height: u64, limit: u64 },
+    /// A frame of a GIF isn't the same size as the first, in pixels.
+    FrameSize { width: u32, height: u32, first_width: u32, first_height: u32 },
+    /// The encoder turned the picture down.
+    Encoding(String),
+}
+
+// The largest width and height each format can record
+const PNG_MAX_SIDE: u64 = (1 << 31) - 1;
+const GIF_MAX_SIDE: u64 = u16::MAX as u64;
+
+// Every picture uses the same palette, in this order
+const GRID: u8 = 0;
+const DEAD: u8 = 1;
+const ALIVE: u8 = 2;
+
+impl Color {
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue }
+    }
+
+    /// Reads a CSS-style colour, "#RRGGBB" or "#RGB".
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let digits = hex.strip_prefix('#')?;
+        if !digits.is_ascii() {
+            return None;
+        }
+        let channel = |range: &str| u8::from_str_radix(range, 16).ok();
+        match digits.len() {
+            6 => Some(Color::rgb(channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?)),
+            3 => Some(Color::rgb(
+                channel(&digits[0..1])? * 0x11,
+                channel(&digits[1..2])? * 0x11,
+                channel(&digits[2..3])? * 0x11,
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+    }
+}
+
+impl Default for ImageStyle {
+    fn default() -> ImageStyle {
+        ImageStyle {
+            cell_size: 10,
+            grid_lines: true,
+            grid_color: Color::rgb(0xCC, 0xCC, 0xCC),
+            dead_color: Color::rgb(0xFF, 0xFF, 0xFF),
+            alive_color: Color::rgb(0x00, 0x00, 0x00),
+        }
+    }
+}
+
+impl ImageStyle {
+    /// The width and height in pixels of a picture of a universe. These can
+    /// be more than an image format allows.
+    pub fn image_size(&self, width: u32, height: u32) -> (u64, u64) {
+        let (cell_size, width, height) = (self.cell_size as u64, width as u64, height as u64);
+        if self.grid_lines {
+            ((cell_size + 1) * width + 1, (cell_size + 1) * height + 1)
+        } else {
+            (cell_size * width, cell_size * height)
+        }
+    }
+
+    // The size of a picture of the universe, if neither side is over `limit`
+    // and every pixel fits in memory.
+    fn checked_image_size(&self, universe: &Universe, limit: u64) -> Result<(u32, u32), ImageError> {
+        let (width, height) = self.image_size(universe.width(), universe.height());
+        // Both sides are under 2^32, so their product can't overflow
+        if width <= limit && height <= limit && width * height <= usize::MAX as u64 {
+            Ok((width as u32, height as u32))
+        } else {
+            Err(ImageError::TooLarge { width, height, limit })
+        }
+    }
+
+    fn palette(&self) -> [Color; 3] {
+        [self.grid_color, self.dead_color, self.alive_color]
+    }
+
+    // Each pixel's palette index, row by row. Cells are laid out as on the
+    // canvas, where each one starts just after a grid line.
+    // The size must already have been checked.
+    fn render(&self, universe: &Universe, (width, height): (u32, u32)) -> Vec<u8> {
+        let (width, height) = (width as usize, height as usize);
+        let cell_size = self.cell_size as usize;
+        let pitch = if self.grid_lines { cell_size + 1 } else { cell_size };
+        let offset = self.grid_lines as usize;
+
+        let mut pixels = vec![if self.grid_lines { GRID } else { DEAD }; width * height];
+        let mut row_pixels = vec![GRID; width];
+        for row in 0..universe.height() {
+            for column in 0..universe.width() {
+                let color = if universe.cell_at(row, column) { ALIVE } else { DEAD };
+                let left = column as usize * pitch + offset;
+                row_pixels[left..left + cell_size].fill(color);
+            }
+
+            let top = row as usize * pitch + offset;
+            for y in top..top + cell_size {
+                pixels[y * width..(y + 1) * width].copy_from_slice(&row_pixels);
+            }
+        }
+        pixels
+    }
+}
+
+/// A picture of a universe as a PNG file, which can be up to 2^31 - 1
+/// pixels wide and high.
+pub fn write_png(universe: &Universe, style: &ImageStyle) -> Result<Vec<u8>, ImageError> {
+    let (width, height) = style.checked_image_size(universe, PNG_MAX_SIDE)?;
+    let pixels = style.render(universe, (width, height));
+    let palette: Vec<u8> = style
+        .palette()
+        .iter()
+        .flat_map(|color| [color.red, color.green, color.blue])
+        .collect();
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(png)
+}
+
+/// Builds an animated GIF one generation at a time, looping forever. GIFs
+/// can be at most 65535 pixels wide and high.
+pub struct GifWriter {
+    style: ImageStyle,
+    delay: u16,
+    size: Option<(u32, u32)>,
+    // Started by the first frame, which sets the size
+    encoder: Option<gif::Encoder<Vec<u8>>>,
+    frames: usize,
+}
+
+impl GifWriter {
+    /// Each frame is shown for `delay_ms` milliseconds, to the nearest 10.
+    pub fn new(style: ImageStyle, delay_ms: u32) -> GifWriter {
+        GifWriter {
+            style,
+            delay: (delay_ms.saturating_add(5) / 10).min(u16::MAX as u32) as u16,
+            size: None,
+            encoder: None,
+            frames: 0,
+        }
+    }
+
+    /// Adds a picture of the universe as it is now, or returns an error and
+    /// adds nothing if the picture would be too large or isn't the same size
+    /// as the first frame.
+    pub fn add_frame(&mut self, universe: &Universe) -> Result<(), ImageError> {
+        let (width, height) = self.style.checked_image_size(universe, GIF_MAX_SIDE)?;
+        match self.size {
+            Some((first_width, first_height)) if (first_width, first_height) != (width, height) => {
+                return Err(ImageError::FrameSize { width, height, first_width, first_height })
+            }
+            Some(_) => {}
+            None => {
+                self.encoder = Some(self.style.gif_encoder(width, height)?);
+                self.size = Some((width, height));
+            }
+        }
+
+        // Each frame covers the whole picture, and is left in place for the
+        // next to cover
+        let pixels = self.style.render(universe, (width, height));
+        let mut frame = gif::Frame::from_indexed_pixels(width as u16, height as u16, pixels, None);
+        frame.delay = self.delay;
+        frame.dispose = gif::DisposalMethod::Keep;
+        let encoder = self.encoder.as_mut().expect("The first frame starts the encoder");
+        encoder.write_frame(&frame)?;
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// How many bytes the file has so far.
+    pub fn byte_size(&self) -> usize {
+        self.encoder.as_ref().map_or(0, |encoder| encoder.get_ref().len())
+    }
+
+    /// The finished file. A GIF without frames has an empty universe's size.
+    pub fn finish(self) -> Result<Vec<u8>, ImageError> {
+        let encoder = match self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                let (width, height) = self.style.image_size(0, 0);
+                self.style.gif_encoder(width as u32, height as u32)?
+            }
+        };
+        Ok(encoder.into_inner()?)
+    }
+}
+
+impl ImageStyle {
+    // Starts a GIF of the given size that loops forever, with the palette
+    // as its global colour table. The size must already have been checked.
+    fn gif_encoder(&self, width: u32, height: u32) -> Result<gif::Encoder<Vec<u8>>, ImageError> {
+        let palette: Vec<u8> = self
+            .palette()
+            .iter()
+            .flat_map(|color| [color.red, color.green, color.blue])
+            .collect();
+        let mut encoder = gif::Encoder::new(Vec::new(), width as u16, height as u16, &palette)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        Ok(encoder)
+    }
+}
+
+/// An animated GIF of the universe's current generation and the
+/// `generations` after it, which ticks the universe forward. A universe too
+/// large for a GIF is left as it was.
+pub fn write_gif(
+    universe: &mut Universe,
+    generations: u64,
+    style: &ImageStyle,
+    delay_ms: u32,
+) -> Result<Vec<u8>, ImageError> {
+    let mut writer = GifWriter::new(*style, delay_ms);
+    writer.add_frame(universe)?;
+    for _ in 0..generations {
+        universe.tick();
+        writer.add_frame(universe)?;
+    }
+    writer.finish()
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageError::TooLarge { width, height, limit } => write!(
+                f,
+                "a {}x{} pixel picture is too large, as this format allows at most {} pixels a side",
+                width, height, limit
+            ),
+            ImageError::FrameSize { width, height, first_width, first_height } => write!(
+                f,
+                "a {}x{} pixel frame doesn't match the GIF's first frame, which is {}x{}",
+                width, height, first_width, first_height
+            ),
+            ImageError::Encoding(message) => write!(f, "couldn't encode the picture: {}", message),
+        }
+    }
+}
+
+impl Error for ImageError {}
+
+impl From<png::EncodingError> for ImageError {
+    fn from(error: png::EncodingError) -> ImageError {
+        ImageError::Encoding(error.to_string())
+    }
+}
+
+impl From<gif::EncodingError> for ImageError {
+    fn from(error: gif::EncodingError) -> ImageError {
+        ImageError::Encoding(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> ImageError {
+        ImageError::Encoding(error.to_string())
+    }
+}
@@ -1,10 +1,68 @@
+mod bitwise;
+mod cells;
+mod census;
+mod changes;
+mod cycle;
+mod engine;
+mod hashlife;
+mod history;
+#[cfg(feature = "image")]
+mod image;
+mod library;
+mod life;
+mod macrocell;
+mod pattern;
+mod plaintext;
+mod random;
+mod rect;
+mod rle;
+mod rule;
+mod sparse;
+mod statistics;
+mod topology;
+mod universe;
+
+#[cfg(feature = "web")]
+mod bindings;
+#[cfg(feature = "web")]
 mod ui;
+#[cfg(feature = "web")]
+mod utils;
+#[cfg(feature = "web")]
+mod web_sys_mixins;
 
-extern crate web_sys;
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "web")]
 use ui::Ui;
 
+#[cfg(feature = "web")]
+pub use bindings::{JsHashLife, JsUniverse};
+
+pub use cells::LiveCells;
+pub use census::{Census, CensusObject};
+pub use changes::ChangeSet;
+pub use cycle::{AutoPause, Stability};
+pub use engine::{Engine, EngineError, Grid};
+pub use hashlife::{HashLife, MAX_STEP_POWER};
+pub use history::HistoryKind;
+#[cfg(feature = "image")]
+pub use image::{write_gif, write_png, Color, GifWriter, ImageError, ImageStyle};
+pub use library::{library_pattern, LibraryPattern, LIBRARY};
+pub use life::{parse_life_105, parse_life_106, write_life_105, write_life_106};
+pub use macrocell::{parse_macrocell, write_macrocell, MAX_MACROCELL_POPULATION};
+pub use pattern::{parse_pattern, Pattern, PatternError, PatternFormat, Rotation};
+pub use plaintext::{parse_plaintext, write_plaintext};
+pub use random::Random;
+pub use rect::Rect;
+pub use rle::{parse_rle, write_rle, MAX_RLE_AREA, MAX_RLE_POPULATION};
+pub use rule::{Rule, RuleParseError};
+pub use sparse::SparseUniverse;
+pub use statistics::{GenerationStats, Statistics};
+pub use topology::Topology;
+pub use universe::{BooleanOp, Overflow, SizeMismatch, Universe, UniverseTooLarge, DEFAULT_CYCLE_WINDOW};
+
 /*
 // A macro to provide `println!(..)`-style syntax for `console.log` logging.
 macro_rules! log {
@@ -13,6 +71,7 @@ macro_rules! log {
     }
 }*/
 
+#[cfg(feature = "web")]
 #[wasm_bindgen]
 pub fn start() {
 	let ui = Ui::new();
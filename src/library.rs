@@ -0,0 +1,65 @@
+use crate::pattern::Pattern;
+use crate::rle::parse_rle;
+
+/// A pattern that ships with the crate, stored as RLE in the `patterns`
+/// directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LibraryPattern {
+    /// A short lowercase identifier, such as "glider".
+    pub id: &'static str,
+    pub name: &'static str,
+    pub rle: &'static str,
+}
+
+macro_rules! library_pattern {
+    ($id:expr, $name:expr) => {
+        LibraryPattern {
+            id: $id,
+            name: $name,
+            rle: include_str!(concat!("../patterns/", $id, ".rle")),
+        }
+    };
+}
+
+/// Every built-in pattern: still lifes, then oscillators, spaceships, guns
+/// and methuselahs.
+pub const LIBRARY: &[LibraryPattern] = &[
+    library_pattern!("block", "Block"),
+    library_pattern!("beehive", "Beehive"),
+    library_pattern!("loaf", "Loaf"),
+    library_pattern!("boat", "Boat"),
+    library_pattern!("tub", "Tub"),
+    library_pattern!("ship", "Ship"),
+    library_pattern!("pond", "Pond"),
+    library_pattern!("barge", "Barge"),
+    library_pattern!("long-boat", "Long boat"),
+    library_pattern!("blinker", "Blinker"),
+    library_pattern!("toad", "Toad"),
+    library_pattern!("beacon", "Beacon"),
+    library_pattern!("pulsar", "Pulsar"),
+    library_pattern!("pentadecathlon", "Pentadecathlon"),
+    library_pattern!("glider", "Glider"),
+    library_pattern!("lwss", "Lightweight spaceship"),
+    library_pattern!("mwss", "Middleweight spaceship"),
+    library_pattern!("hwss", "Heavyweight spaceship"),
+    library_pattern!("gosper-glider-gun", "Gosper glider gun"),
+    library_pattern!("r-pentomino", "R-pentomino"),
+    library_pattern!("acorn", "Acorn"),
+    library_pattern!("diehard", "Diehard"),
+];
+
+impl LibraryPattern {
+    /// Parses the bundled RLE. Every file is checked by the tests, so this
+    /// doesn't fail.
+    pub fn pattern(&self) -> Pattern {
+        parse_rle(self.rle).unwrap_or_else(|error| panic!("Bundled pattern {} is invalid: {}", self.id, error))
+    }
+}
+
+/// Looks up a built-in pattern by its id or name, ignoring case.
+pub fn library_pattern(name: &str) -> Option<Pattern> {
+    LIBRARY
+        .iter()
+        .find(|entry| entry.id.eq_ignore_ascii_case(name) || entry.name.eq_ignore_ascii_case(name))
+        .map(LibraryPattern::pattern)
+}
@@ -0,0 +1,179 @@
+//! Life 1.05 and Life 1.06 patterns, as described at
+//! https://conwaylife.com/wiki/Life_1.05 and https://conwaylife.com/wiki/Life_1.06
+
+use crate::pattern::{header_line_index, Pattern, PatternError};
+use crate::rule::Rule;
+
+pub const LIFE_105_HEADER: &str = "#Life 1.05";
+pub const LIFE_106_HEADER: &str = "#Life 1.06";
+
+pub fn parse_life_105(input: &str) -> Result<Pattern, PatternError> {
+    let mut cells = Vec::new();
+    let mut name = None;
+    let mut author = None;
+    let mut comments = Vec::new();
+    let mut rule = None;
+    let mut block_origin: Option<(i64, i64)> = None;
+    let mut block_row = 0i64;
+    let header_index = header_line_index(input);
+
+    for (line_index, line) in input.lines().enumerate().skip(header_index) {
+        let line_number = line_index + 1;
+        let line = line.trim_end();
+
+        if line_index == header_index {
+            expect_header(line, LIFE_105_HEADER, line_number)?;
+            continue;
+        }
+
+        if let Some(directive) = line.strip_prefix('#') {
+            let mut chars = directive.chars();
+            let kind = chars.next();
+            let rest = chars.as_str().trim();
+
+            match kind {
+                // The name and author are written as tagged descriptions,
+                // as plaintext files do with "!Name:"
+                Some('D') => {
+                    if let Some(text) = rest.strip_prefix("Name:") {
+                        name = Some(text.trim().to_string());
+                    } else if let Some(text) = rest.strip_prefix("Author:") {
+                        author = Some(text.trim().to_string());
+                    } else {
+                        comments.push(rest.to_string());
+                    }
+                }
+                Some('N') => rule = Some(Rule::conway()),
+                Some('R') => {
+                    rule = Some(Rule::parse(rest).map_err(|e| PatternError::new(line_number, 4, e.to_string()))?)
+                }
+                Some('P') => {
+                    let (x, y) = parse_coordinates(rest, line_number, 4)?;
+                    block_origin = Some((y, x));
+                    block_row = 0;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let (top, left) = block_origin
+            .ok_or_else(|| PatternError::new(line_number, 1, "cells must follow a #P line"))?;
+
+        for (column, c) in line.chars().enumerate() {
+            match c {
+                '.' => {}
+                '*' => cells.push((top + block_row, left + column as i64)),
+                c => {
+                    return Err(PatternError::new(
+                        line_number,
+                        column + 1,
+                        format!("unexpected character '{}'", c)))
+                }
+            }
+        }
+        block_row += 1;
+    }
+
+    let mut pattern = Pattern::from_signed_cells(&cells, input.lines().count())?;
+    pattern.name = name;
+    pattern.author = author;
+    pattern.comments = comments;
+    pattern.rule = rule;
+    Ok(pattern)
+}
+
+pub fn parse_life_106(input: &str) -> Result<Pattern, PatternError> {
+    let mut cells = Vec::new();
+    let header_index = header_line_index(input);
+
+    for (line_index, line) in input.lines().enumerate().skip(header_index) {
+        let line_number = line_index + 1;
+
+        if line_index == header_index {
+            expect_header(line.trim_end(), LIFE_106_HEADER, line_number)?;
+            continue;
+        }
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (x, y) = parse_coordinates(line, line_number, 1)?;
+        cells.push((y, x));
+    }
+
+    Pattern::from_signed_cells(&cells, input.lines().count())
+}
+
+pub fn write_life_105(pattern: &Pattern) -> String {
+    let mut output = format!("{}\n", LIFE_105_HEADER);
+
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("#D Name: {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("#D Author: {}\n", author));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("#D {}\n", comment));
+    }
+
+    match pattern.rule {
+        None => {}
+        Some(rule) if rule == Rule::conway() => output.push_str("#N\n"),
+        Some(rule) => {
+            let counts = |included: &dyn Fn(u8) -> bool| -> String {
+                (0..=8).filter(|count| included(*count)).map(|count| count.to_string()).collect()
+            };
+            output.push_str(&format!(
+                "#R {}/{}\n",
+                counts(&|count| rule.is_survival(count)),
+                counts(&|count| rule.is_birth(count))));
+        }
+    }
+
+    // Centre the pattern on the origin, as other programs do
+    output.push_str(&format!(
+        "#P {} {}\n",
+        -(pattern.width() as i64 / 2),
+        -(pattern.height() as i64 / 2)));
+
+    for row in 0..pattern.height() {
+        let line: String = (0..pattern.width())
+            .map(|column| if pattern.cell_at(row, column) { '*' } else { '.' })
+            .collect();
+        let line = line.trim_end_matches('.');
+        output.push_str(if line.is_empty() { "." } else { line });
+        output.push('\n');
+    }
+
+    output
+}
+
+pub fn write_life_106(pattern: &Pattern) -> String {
+    let mut output = format!("{}\n", LIFE_106_HEADER);
+    for (row, column) in pattern.live_cells() {
+        output.push_str(&format!("{} {}\n", column, row));
+    }
+    output
+}
+
+fn expect_header(line: &str, header: &str, line_number: usize) -> Result<(), PatternError> {
+    if line.trim_start() == header {
+        Ok(())
+    } else {
+        Err(PatternError::new(line_number, 1, format!("expected '{}'", header)))
+    }
+}
+
+// Reads an "x y" pair, as used by Life 1.06 cells and Life 1.05 #P lines.
+fn parse_coordinates(text: &str, line_number: usize, column: usize) -> Result<(i64, i64), PatternError> {
+    let invalid = || PatternError::new(line_number, column, format!("expected 'x y' coordinates, found '{}'", text.trim()));
+
+    let mut parts = text.split_whitespace().map(|part| part.parse::<i64>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,340 @@
+//! Golly's macrocell (.mc) format, as described at
+//! https://conwaylife.com/wiki/Macrocell
+//!
+//! A macrocell file lists the nodes of a quadtree, one per line, each
+//! referring to earlier nodes by number. Leaves are 8×8 blocks written with
+//! '.', '*' and '$'; larger nodes are written as "level nw ne sw se", where 0
+//! stands for an empty child. The last node is the root.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::pattern::{header_line_index, Pattern, PatternError};
+use crate::rule::Rule;
+
+pub const MACROCELL_HEADER: &str = "[M2]";
+
+const LEAF_LEVEL: u32 = 3;
+const LEAF_SIZE: u32 = 1 << LEAF_LEVEL;
+
+// Larger trees can't be positioned with 64-bit coordinates.
+const MAX_LEVEL: u32 = 62;
+
+// A few lines can describe a tree with astronomically many live cells, so
+// files are checked against this before any cells are listed.
+pub const MAX_MACROCELL_POPULATION: u64 = 1 << 24;
+
+enum Node {
+    Leaf(u64),
+    Branch { level: u32, children: [usize; 4] },
+}
+
+pub fn parse_macrocell(input: &str) -> Result<Pattern, PatternError> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut pattern = Pattern::default();
+    let header_index = header_line_index(input);
+
+    for (line_index, line) in input.lines().enumerate().skip(header_index) {
+        let line_number = line_index + 1;
+        let line = line.trim_end();
+
+        if line_index == header_index {
+            if !line.trim_start().starts_with(MACROCELL_HEADER) {
+                return Err(PatternError::new(line_number, 1, format!("expected '{}'", MACROCELL_HEADER)));
+            }
+            continue;
+        }
+
+        if let Some(directive) = line.strip_prefix('#') {
+            let mut chars = directive.chars();
+            let kind = chars.next();
+            let text = chars.as_str().trim().to_string();
+
+            match kind {
+                Some('N') => pattern.name = Some(text),
+                Some('O') => pattern.author = Some(text),
+                Some('C') | Some('c') => pattern.comments.push(text),
+                Some('R') => {
+                    let rule = Rule::parse(text.split(':').next().unwrap_or(""))
+                        .map_err(|e| PatternError::new(line_number, 4, e.to_string()))?;
+                    pattern.rule = Some(rule);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let node = if line.starts_with(|c: char| c.is_ascii_digit()) {
+            parse_branch(line, line_number, &nodes)?
+        } else {
+            parse_leaf(line, line_number)?
+        };
+        nodes.push(node);
+    }
+
+    // Sizing the pattern up from the node table takes time in proportion to
+    // the number of lines, however many cells they describe
+    let summaries = summarize(&nodes);
+    if let Some(root) = summaries.last() {
+        let last_line = input.lines().count();
+        if root.population > MAX_MACROCELL_POPULATION {
+            return Err(PatternError::new(
+                last_line,
+                1,
+                format!("pattern has more than {} live cells", MAX_MACROCELL_POPULATION)));
+        }
+        if let Some((top, left, bottom, right)) = root.bounds {
+            if bottom - top >= u32::MAX as i64 || right - left >= u32::MAX as i64 {
+                return Err(PatternError::new(last_line, 1, "pattern is too large"));
+            }
+        }
+    }
+
+    let mut cells = Vec::new();
+    if !nodes.is_empty() {
+        collect_cells(&nodes, &summaries, nodes.len(), 0, 0, &mut cells);
+    }
+
+    let mut live = Pattern::from_signed_cells(&cells, input.lines().count())?;
+    live.name = pattern.name;
+    live.author = pattern.author;
+    live.comments = pattern.comments;
+    live.rule = pattern.rule;
+    Ok(live)
+}
+
+pub fn write_macrocell(pattern: &Pattern) -> String {
+    let mut output = format!("{} (wasm-game-of-life)\n", MACROCELL_HEADER);
+
+    if let Some(rule) = pattern.rule {
+        output.push_str(&format!("#R {}\n", rule));
+    }
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("#C {}\n", comment));
+    }
+
+    // Gather the live cells into 8×8 leaves
+    let mut leaves: BTreeMap<(u32, u32), u64> = BTreeMap::new();
+    for (row, column) in pattern.live_cells() {
+        let bit = (row % LEAF_SIZE) * LEAF_SIZE + column % LEAF_SIZE;
+        *leaves.entry((row / LEAF_SIZE, column / LEAF_SIZE)).or_insert(0) |= 1 << bit;
+    }
+
+    if leaves.is_empty() {
+        output.push_str("$\n");
+        return output;
+    }
+
+    let mut node_count = 0;
+    let mut leaf_ids: HashMap<u64, usize> = HashMap::new();
+    let mut level_nodes: BTreeMap<(u32, u32), usize> = BTreeMap::new();
+    for (position, bits) in leaves {
+        let id = *leaf_ids.entry(bits).or_insert_with(|| {
+            output.push_str(&write_leaf(bits));
+            node_count += 1;
+            node_count
+        });
+        level_nodes.insert(position, id);
+    }
+
+    // Combine quadrants level by level until a single root covers the pattern
+    let size = pattern.width().max(pattern.height()).max(LEAF_SIZE);
+    let root_level = 32 - (size - 1).leading_zeros();
+    let mut branch_ids: HashMap<(u32, [usize; 4]), usize> = HashMap::new();
+    for level in LEAF_LEVEL + 1..=root_level {
+        let mut parents: BTreeMap<(u32, u32), [usize; 4]> = BTreeMap::new();
+        for ((row, column), id) in level_nodes {
+            let quadrant = ((row % 2) * 2 + column % 2) as usize;
+            parents.entry((row / 2, column / 2)).or_insert([0; 4])[quadrant] = id;
+        }
+
+        level_nodes = BTreeMap::new();
+        for (position, children) in parents {
+            let id = *branch_ids.entry((level, children)).or_insert_with(|| {
+                output.push_str(&format!(
+                    "{} {} {} {} {}\n",
+                    level, children[0], children[1], children[2], children[3]));
+                node_count += 1;
+                node_count
+            });
+            level_nodes.insert(position, id);
+        }
+    }
+
+    output
+}
+
+fn parse_leaf(line: &str, line_number: usize) -> Result<Node, PatternError> {
+    let mut bits = 0u64;
+    let mut row = 0;
+    let mut column = 0;
+
+    for (index, c) in line.chars().enumerate() {
+        let error = |message: &str| PatternError::new(line_number, index + 1, message);
+        match c {
+            '.' | '*' => {
+                if row >= LEAF_SIZE || column >= LEAF_SIZE {
+                    return Err(error("leaf is larger than 8×8"));
+                }
+                if c == '*' {
+                    bits |= 1 << (row * LEAF_SIZE + column);
+                }
+                column += 1;
+            }
+            '$' => {
+                row += 1;
+                column = 0;
+            }
+            _ => return Err(error(&format!("unexpected character '{}'", c))),
+        }
+    }
+
+    Ok(Node::Leaf(bits))
+}
+
+fn parse_branch(line: &str, line_number: usize, nodes: &[Node]) -> Result<Node, PatternError> {
+    let mut numbers = Vec::new();
+    let mut column = 1;
+    for part in line.split(' ') {
+        let number = part
+            .parse::<usize>()
+            .map_err(|_| PatternError::new(line_number, column, format!("'{}' is not a number", part)))?;
+        numbers.push((number, column));
+        column += part.chars().count() + 1;
+    }
+
+    if numbers.len() != 5 {
+        return Err(PatternError::new(line_number, 1, "expected a level and four children"));
+    }
+
+    let (level, _) = numbers[0];
+    if level <= LEAF_LEVEL as usize || level > MAX_LEVEL as usize {
+        return Err(PatternError::new(
+            line_number,
+            1,
+            format!("level must be between {} and {}", LEAF_LEVEL + 1, MAX_LEVEL)));
+    }
+    let level = level as u32;
+
+    let mut children = [0; 4];
+    for (child, (id, column)) in children.iter_mut().zip(numbers[1..].iter().cloned()) {
+        if id > nodes.len() {
+            return Err(PatternError::new(line_number, column, format!("node {} has not been defined", id)));
+        }
+        if id != 0 && node_level(&nodes[id - 1]) != level - 1 {
+            return Err(PatternError::new(line_number, column, format!("node {} is not at level {}", id, level - 1)));
+        }
+        *child = id;
+    }
+
+    Ok(Node::Branch { level, children })
+}
+
+fn node_level(node: &Node) -> u32 {
+    match node {
+        Node::Leaf(_) => LEAF_LEVEL,
+        Node::Branch { level, .. } => *level,
+    }
+}
+
+// The live cells in a node and their bounds, as (top, left, bottom, right)
+// relative to its top-left corner.
+struct Summary {
+    population: u64,
+    bounds: Option<(i64, i64, i64, i64)>,
+}
+
+// Summarizes every node, relying on children being listed before parents.
+fn summarize(nodes: &[Node]) -> Vec<Summary> {
+    let mut summaries: Vec<Summary> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let summary = match node {
+            Node::Leaf(bits) => {
+                let bounds = (0..64).filter(|bit| bits & (1 << bit) != 0).fold(None, |bounds, bit| {
+                    let (row, column) = ((bit / LEAF_SIZE) as i64, (bit % LEAF_SIZE) as i64);
+                    Some(extend(bounds, (row, column, row, column)))
+                });
+                Summary { population: bits.count_ones() as u64, bounds }
+            }
+            Node::Branch { level, children } => {
+                let half = 1i64 << (level - 1);
+                let offsets = [(0, 0), (0, half), (half, 0), (half, half)];
+                let mut population = 0u64;
+                let mut bounds: Option<(i64, i64, i64, i64)> = None;
+                for (id, (row, column)) in children.iter().zip(offsets.iter()) {
+                    if *id == 0 {
+                        continue;
+                    }
+                    let child = &summaries[id - 1];
+                    population = population.saturating_add(child.population);
+                    if let Some((top, left, bottom, right)) = child.bounds {
+                        bounds = Some(extend(bounds, (top + row, left + column, bottom + row, right + column)));
+                    }
+                }
+                Summary { population, bounds }
+            }
+        };
+        summaries.push(summary);
+    }
+    summaries
+}
+
+// The smallest bounds holding both.
+fn extend(bounds: Option<(i64, i64, i64, i64)>, other: (i64, i64, i64, i64)) -> (i64, i64, i64, i64) {
+    match bounds {
+        None => other,
+        Some((top, left, bottom, right)) => (top.min(other.0), left.min(other.1), bottom.max(other.2), right.max(other.3)),
+    }
+}
+
+fn collect_cells(nodes: &[Node], summaries: &[Summary], id: usize, top: i64, left: i64, cells: &mut Vec<(i64, i64)>) {
+    // Empty nodes are skipped, so the work is bounded by the population
+    if id == 0 || summaries[id - 1].population == 0 {
+        return;
+    }
+
+    match &nodes[id - 1] {
+        Node::Leaf(bits) => {
+            for bit in 0..64 {
+                if bits & (1 << bit) != 0 {
+                    cells.push((top + (bit / LEAF_SIZE) as i64, left + (bit % LEAF_SIZE) as i64));
+                }
+            }
+        }
+        Node::Branch { level, children } => {
+            let half = 1i64 << (level - 1);
+            collect_cells(nodes, summaries, children[0], top, left, cells);
+            collect_cells(nodes, summaries, children[1], top, left + half, cells);
+            collect_cells(nodes, summaries, children[2], top + half, left, cells);
+            collect_cells(nodes, summaries, children[3], top + half, left + half, cells);
+        }
+    }
+}
+
+fn write_leaf(bits: u64) -> String {
+    let mut rows: Vec<String> = (0..LEAF_SIZE)
+        .map(|row| {
+            let line: String = (0..LEAF_SIZE)
+                .map(|column| if bits & (1 << (row * LEAF_SIZE + column)) != 0 { '*' } else { '.' })
+                .collect();
+            line.trim_end_matches('.').to_string()
+        })
+        .collect();
+
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let mut leaf: String = rows.iter().map(|row| format!("{}$", row)).collect();
+    leaf.push('\n');
+    leaf
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+
+use crate::life::{parse_life_105, parse_life_106, write_life_105, write_life_106, LIFE_105_HEADER, LIFE_106_HEADER};
+use crate::macrocell::{parse_macrocell, write_macrocell, MACROCELL_HEADER};
+use crate::plaintext::{parse_plaintext, write_plaintext};
+use crate::rle::{parse_rle, write_rle};
+use crate::rule::Rule;
+
+/// A pattern read from, or about to be written to, a pattern file: the live
+/// cells of a rectangular region along with the file's metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    width: u32,
+    height: u32,
+    cells: BTreeSet<(u32, u32)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "web", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum PatternFormat {
+    Rle,
+    Plaintext,
+    Life105,
+    Life106,
+    Macrocell,
+}
+
+/// A clockwise turn by a multiple of 90 degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+/// An error in a pattern file, pointing at the offending line and column
+/// (both counted from 1).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Pattern {
+    pub fn new(width: u32, height: u32) -> Pattern {
+        Pattern {
+            width,
+            height,
+            ..Pattern::default()
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn cell_at(&self, row: u32, column: u32) -> bool {
+        self.cells.contains(&(row, column))
+    }
+
+    /// Setting a live cell outside the pattern grows it to fit.
+    pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
+        if alive {
+            self.width = self.width.max(column + 1);
+            self.height = self.height.max(row + 1);
+            self.cells.insert((row, column));
+        } else {
+            self.cells.remove(&(row, column));
+        }
+    }
+
+    // Builds a pattern from cells on a plane with signed coordinates, such as
+    // those in Life 1.06 files, moving them so the top-left live cell's row
+    // and column are both 0. Cells too far apart for a pattern's rows and
+    // columns are an error on `line`.
+    pub(crate) fn from_signed_cells(cells: &[(i64, i64)], line: usize) -> Result<Pattern, PatternError> {
+        let rows = || cells.iter().map(|(row, _)| *row);
+        let columns = || cells.iter().map(|(_, column)| *column);
+        let (top, left) = (rows().min().unwrap_or(0), columns().min().unwrap_or(0));
+        let fits = |low: i64, high: i64| high.checked_sub(low).is_some_and(|span| span < u32::MAX as i64);
+        if !fits(top, rows().max().unwrap_or(0)) || !fits(left, columns().max().unwrap_or(0)) {
+            return Err(PatternError::new(line, 1, "pattern is too large"));
+        }
+
+        let mut pattern = Pattern::default();
+        for (row, column) in cells {
+            pattern.set_cell((row - top) as u32, (column - left) as u32, true);
+        }
+        Ok(pattern)
+    }
+
+    // Sets the size given in a file's header, growing it if necessary to fit
+    // cells that have already been read.
+    pub(crate) fn set_bounds(&mut self, width: u32, height: u32) {
+        let (right, bottom) = self.cells.iter().fold((0, 0), |(right, bottom), (row, column)| {
+            (right.max(column + 1), bottom.max(row + 1))
+        });
+        self.width = width.max(right);
+        self.height = height.max(bottom);
+    }
+
+    /// The live cells as (row, column) pairs, in row-major order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.cells.iter().cloned()
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// A copy of the pattern, first mirrored left-to-right if `reflect` is
+    /// set and then rotated. The metadata is kept as it is.
+    pub fn transformed(&self, rotation: Rotation, reflect: bool) -> Pattern {
+        let (width, height) = (self.width, self.height);
+        let (new_width, new_height) = match rotation {
+            Rotation::None | Rotation::Clockwise180 => (width, height),
+            Rotation::Clockwise90 | Rotation::Clockwise270 => (height, width),
+        };
+
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(row, column)| {
+                let column = if reflect { width - 1 - column } else { column };
+                match rotation {
+                    Rotation::None => (row, column),
+                    Rotation::Clockwise90 => (column, height - 1 - row),
+                    Rotation::Clockwise180 => (height - 1 - row, width - 1 - column),
+                    Rotation::Clockwise270 => (width - 1 - column, row),
+                }
+            })
+            .collect();
+
+        Pattern {
+            name: self.name.clone(),
+            author: self.author.clone(),
+            comments: self.comments.clone(),
+            rule: self.rule,
+            width: new_width,
+            height: new_height,
+            cells,
+        }
+    }
+}
+
+impl Rotation {
+    /// The rotation after turning another 90 degrees clockwise.
+    pub fn next(self) -> Rotation {
+        match self {
+            Rotation::None => Rotation::Clockwise90,
+            Rotation::Clockwise90 => Rotation::Clockwise180,
+            Rotation::Clockwise180 => Rotation::Clockwise270,
+            Rotation::Clockwise270 => Rotation::None,
+        }
+    }
+}
+
+impl PatternFormat {
+    /// Guesses a pattern file's format from its contents.
+    pub fn detect(input: &str) -> PatternFormat {
+        let trimmed = input.trim_start();
+
+        if trimmed.starts_with(MACROCELL_HEADER) {
+            return PatternFormat::Macrocell;
+        }
+        if trimmed.starts_with(LIFE_106_HEADER) {
+            return PatternFormat::Life106;
+        }
+        if trimmed.starts_with(LIFE_105_HEADER) {
+            return PatternFormat::Life105;
+        }
+        if trimmed.starts_with('!') {
+            return PatternFormat::Plaintext;
+        }
+
+        // Without a header, a grid of '.' and 'O' is plaintext; anything else
+        // is most likely RLE.
+        let is_grid = trimmed
+            .lines()
+            .all(|line| line.trim_end().chars().all(|c| c == '.' || c == 'O' || c == '*'));
+        if is_grid {
+            PatternFormat::Plaintext
+        } else {
+            PatternFormat::Rle
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Pattern, PatternError> {
+        match self {
+            PatternFormat::Rle => parse_rle(input),
+            PatternFormat::Plaintext => parse_plaintext(input),
+            PatternFormat::Life105 => parse_life_105(input),
+            PatternFormat::Life106 => parse_life_106(input),
+            PatternFormat::Macrocell => parse_macrocell(input),
+        }
+    }
+
+    pub fn write(&self, pattern: &Pattern) -> String {
+        match self {
+            PatternFormat::Rle => write_rle(pattern),
+            PatternFormat::Plaintext => write_plaintext(pattern),
+            PatternFormat::Life105 => write_life_105(pattern),
+            PatternFormat::Life106 => write_life_106(pattern),
+            PatternFormat::Macrocell => write_macrocell(pattern),
+        }
+    }
+}
+
+/// Reads a pattern in any supported format, detecting which from its contents.
+pub fn parse_pattern(input: &str) -> Result<Pattern, PatternError> {
+    PatternFormat::detect(input).parse(input)
+}
+
+impl PatternError {
+    pub fn new<S: Into<String>>(line: usize, column: usize, message: S) -> PatternError {
+        PatternError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for PatternError {}
+
+// The index of the line a header is expected on: the first that isn't
+// blank, as `PatternFormat::detect` skips leading whitespace.
+pub(crate) fn header_line_index(input: &str) -> usize {
+    input.lines().position(|line| !line.trim().is_empty()).unwrap_or(0)
+}
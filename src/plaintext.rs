@@ -0,0 +1,67 @@
+//! Plaintext (.cells) patterns, as described at
+//! https://conwaylife.com/wiki/Plaintext
+
+use crate::pattern::{Pattern, PatternError};
+
+pub fn parse_plaintext(input: &str) -> Result<Pattern, PatternError> {
+    let mut pattern = Pattern::default();
+    let mut row = 0u32;
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line_number = line_index + 1;
+
+        if let Some(comment) = line.strip_prefix('!') {
+            let comment = comment.trim();
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.name = Some(name.trim().to_string());
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.author = Some(author.trim().to_string());
+            } else if !comment.is_empty() {
+                pattern.comments.push(comment.to_string());
+            }
+            continue;
+        }
+
+        for (column, c) in line.trim_end().chars().enumerate() {
+            match c {
+                '.' => {}
+                'O' | '*' => pattern.set_cell(row, column as u32, true),
+                c => {
+                    return Err(PatternError::new(
+                        line_number,
+                        column + 1,
+                        format!("unexpected character '{}'", c)))
+                }
+            }
+        }
+
+        let width = line.trim_end().chars().count() as u32;
+        row += 1;
+        pattern.set_bounds(width, row);
+    }
+
+    Ok(pattern)
+}
+
+pub fn write_plaintext(pattern: &Pattern) -> String {
+    let mut output = String::new();
+
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("!Name: {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("!Author: {}\n", author));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("!{}\n", comment));
+    }
+
+    for row in 0..pattern.height() {
+        for column in 0..pattern.width() {
+            output.push(if pattern.cell_at(row, column) { 'O' } else { '.' });
+        }
+        output.push('\n');
+    }
+
+    output
+}
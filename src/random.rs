@@ -0,0 +1,50 @@
+/// A small seeded pseudo-random number generator (SplitMix64). It only uses
+/// 64-bit integer arithmetic, so a seed produces the same sequence in the
+/// browser, natively and in tests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in [0, 1), with 53 bits of precision.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A seed for when the caller doesn't care which soup they get. JavaScript's
+/// Math.random is only there when running in a browser, even if the web
+/// feature is enabled.
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+pub(crate) fn new_seed() -> u64 {
+    // Math.random has at most 53 bits of precision, so draw twice
+    let high = (js_sys::Math::random() * (1u64 << 32) as f64) as u64;
+    let low = (js_sys::Math::random() * (1u64 << 32) as f64) as u64;
+    (high << 32) | low
+}
+
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+pub(crate) fn new_seed() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    // Mixed with a counter so seeds picked within the clock's resolution
+    // still differ
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+    Random::new(nanos ^ COUNTER.fetch_add(1, Ordering::Relaxed).rotate_left(32)).next_u64()
+}
@@ -0,0 +1,47 @@
+/// A rectangle of cells: `height` rows starting at `row`, and `width` columns
+/// starting at `column`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub row: u32,
+    pub column: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(row: u32, column: u32, width: u32, height: u32) -> Rect {
+        Rect { row, column, width, height }
+    }
+
+    /// The row just below the rectangle, or `u32::MAX` for one that reaches
+    /// that far.
+    pub fn bottom(&self) -> u32 {
+        self.row.saturating_add(self.height)
+    }
+
+    /// The column just past the rectangle's right edge, or `u32::MAX` for
+    /// one that reaches that far.
+    pub fn right(&self) -> u32 {
+        self.column.saturating_add(self.width)
+    }
+
+    /// The part of the rectangle inside a grid of `width` columns and
+    /// `height` rows starting at (0, 0). A rectangle entirely outside it
+    /// keeps its corner and becomes empty.
+    pub fn clipped(&self, width: u32, height: u32) -> Rect {
+        Rect::new(
+            self.row,
+            self.column,
+            self.right().min(width).saturating_sub(self.column),
+            self.bottom().min(height).saturating_sub(self.row),
+        )
+    }
+
+    pub fn contains(&self, row: u32, column: u32) -> bool {
+        row >= self.row && row < self.bottom() && column >= self.column && column < self.right()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
@@ -0,0 +1,239 @@
+//! Run Length Encoded (.rle) patterns, as described at
+//! https://conwaylife.com/wiki/Run_Length_Encoded
+
+use crate::pattern::{Pattern, PatternError};
+use crate::rule::Rule;
+
+// Golly and most other tools keep RLE lines under 70 characters.
+const MAX_LINE_LENGTH: usize = 70;
+
+// A few characters, such as "4000000000o", can ask for far more cells than
+// fit in memory, so files are held to these.
+pub const MAX_RLE_POPULATION: u64 = 1 << 24;
+pub const MAX_RLE_AREA: u64 = 1 << 30;
+
+pub fn parse_rle(input: &str) -> Result<Pattern, PatternError> {
+    let mut pattern = Pattern::default();
+    let mut in_body = false;
+    let mut row = 0u32;
+    let mut column = 0u32;
+    let mut run_count: Option<u32> = None;
+
+    for (line_index, line) in input.lines().enumerate() {
+        let line_number = line_index + 1;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if !in_body {
+            if trimmed.starts_with('#') {
+                parse_comment(&mut pattern, line, line_number)?;
+                continue;
+            }
+
+            in_body = true;
+            if trimmed.starts_with('x') {
+                parse_header(&mut pattern, line, line_number)?;
+                continue;
+            }
+        }
+
+        for (char_index, c) in line.chars().enumerate() {
+            let column_number = char_index + 1;
+            let error = |message: String| PatternError::new(line_number, column_number, message);
+
+            if let Some(digit) = c.to_digit(10) {
+                let count = run_count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|count| count.checked_add(digit))
+                    .ok_or_else(|| error("run count is too large".to_string()))?;
+                run_count = Some(count);
+                continue;
+            }
+
+            if c.is_whitespace() {
+                continue;
+            }
+
+            let count = run_count.take().unwrap_or(1);
+            match c {
+                'b' | '.' | 'o' | 'A' => {
+                    let end = column
+                        .checked_add(count)
+                        .ok_or_else(|| error("pattern is too wide".to_string()))?;
+                    if c == 'o' || c == 'A' {
+                        if pattern.population() as u64 + count as u64 > MAX_RLE_POPULATION {
+                            return Err(error(format!("pattern has more than {} live cells", MAX_RLE_POPULATION)));
+                        }
+                        let area = pattern.width().max(end) as u64 * (pattern.height() as u64).max(row as u64 + 1);
+                        if area > MAX_RLE_AREA {
+                            return Err(error(format!("pattern covers more than {} cells", MAX_RLE_AREA)));
+                        }
+                        for live_column in column..end {
+                            pattern.set_cell(row, live_column, true);
+                        }
+                    }
+                    column = end;
+                }
+                '$' => {
+                    row = row
+                        .checked_add(count)
+                        .ok_or_else(|| error("pattern is too tall".to_string()))?;
+                    column = 0;
+                }
+                '!' => return Ok(pattern),
+                c => return Err(error(format!("unexpected character '{}'", c))),
+            }
+        }
+    }
+
+    if run_count.is_some() {
+        return Err(PatternError::new(input.lines().count(), 1, "run count at end of pattern is not followed by a tag"));
+    }
+
+    Ok(pattern)
+}
+
+pub fn write_rle(pattern: &Pattern) -> String {
+    let mut output = String::new();
+
+    if let Some(name) = &pattern.name {
+        output.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        output.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("#C {}\n", comment));
+    }
+
+    output.push_str(&format!(
+        "x = {}, y = {}, rule = {}\n",
+        pattern.width(),
+        pattern.height(),
+        pattern.rule.unwrap_or_default()
+    ));
+
+    let mut tokens = Vec::new();
+    let mut cells = pattern.live_cells().peekable();
+    let mut current_row = 0;
+    let mut current_column = 0;
+    while let Some((row, column)) = cells.next() {
+        if row > current_row {
+            tokens.push(run(row - current_row, '$'));
+            current_row = row;
+            current_column = 0;
+        }
+
+        // Extend this run of live cells as far as it goes
+        let mut end = column + 1;
+        while cells.peek() == Some(&(row, end)) {
+            cells.next();
+            end += 1;
+        }
+
+        if column > current_column {
+            tokens.push(run(column - current_column, 'b'));
+        }
+        tokens.push(run(end - column, 'o'));
+        current_column = end;
+    }
+    tokens.push("!".to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + token.len() > MAX_LINE_LENGTH {
+            output.push_str(&line);
+            output.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+
+    output.push_str(&line);
+    output.push('\n');
+    output
+}
+
+fn parse_comment(pattern: &mut Pattern, line: &str, line_number: usize) -> Result<(), PatternError> {
+    let mut chars = line.trim_start().chars();
+    chars.next();
+    let kind = chars.next();
+    let rest = chars.as_str().trim_start();
+    let text = rest.trim_end().to_string();
+    let text_column = line.len() - rest.len() + 1;
+
+    match kind {
+        Some('N') => pattern.name = Some(text),
+        Some('O') => pattern.author = Some(text),
+        Some('C') | Some('c') => pattern.comments.push(text),
+        Some('r') => pattern.rule = Some(parse_rule(&text, line_number, text_column)?),
+        // Offsets (#P, #R) and anything else we don't use
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn parse_header(pattern: &mut Pattern, line: &str, line_number: usize) -> Result<(), PatternError> {
+    let mut width = None;
+    let mut height = None;
+
+    let mut offset = 0;
+    for part in line.split(',') {
+        let part_column = offset + 1;
+        offset += part.chars().count() + 1;
+
+        let (key, raw_value) = match part.find('=') {
+            Some(equals) => (part[..equals].trim(), &part[equals + 1..]),
+            None => {
+                return Err(PatternError::new(line_number, part_column, format!("expected '=' after '{}'", part.trim())))
+            }
+        };
+        let value = raw_value.trim();
+        let value_column = part_column + (part.len() - raw_value.trim_start().len());
+        let parse_size = |value: &str| {
+            value.parse::<u32>().map_err(|_| {
+                PatternError::new(line_number, value_column, format!("'{}' is not a valid size", value))
+            })
+        };
+
+        match key {
+            "x" => width = Some(parse_size(value)?),
+            "y" => height = Some(parse_size(value)?),
+            "rule" => pattern.rule = Some(parse_rule(value, line_number, value_column)?),
+            _ => {}
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) if width as u64 * height as u64 > MAX_RLE_AREA => Err(PatternError::new(
+            line_number,
+            1,
+            format!("pattern covers more than {} cells", MAX_RLE_AREA),
+        )),
+        (Some(width), Some(height)) => {
+            pattern.set_bounds(width, height);
+            Ok(())
+        }
+        _ => Err(PatternError::new(line_number, 1, "header must give both x and y")),
+    }
+}
+
+fn parse_rule(rulestring: &str, line_number: usize, column: usize) -> Result<Rule, PatternError> {
+    // Golly appends the topology to the rule ("B3/S23:T20,20"); it's not part
+    // of the rule itself.
+    let rulestring = rulestring.split(':').next().unwrap_or("");
+    Rule::parse(rulestring).map_err(|e| PatternError::new(line_number, column, e.to_string()))
+}
+
+fn run(count: u32, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
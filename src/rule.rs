@@ -0,0 +1,248 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A Life-like cellular automaton rule: the neighbour counts that cause a
+/// dead cell to be born, and the counts that let a live cell survive.
+///
+/// Rules are usually written as rulestrings, either in B/S notation
+/// ("B3/S23") or in the older S/B notation ("23/3").
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    // Bit n is set when a cell with n live neighbours is born / survives.
+    birth: u16,
+    survival: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    Empty,
+    MissingBirth,
+    MissingSurvival,
+    TooManySections,
+    InvalidCharacter { character: char, position: usize },
+    InvalidNeighborCount { count: char, position: usize },
+    DuplicateNeighborCount { count: char, position: usize },
+}
+
+impl Rule {
+    /// Builds a rule from the neighbour counts that cause birth and survival.
+    /// Counts above 8 are ignored.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Rule {
+        Rule {
+            birth: Rule::mask(birth),
+            survival: Rule::mask(survival),
+        }
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn conway() -> Rule {
+        Rule::new(&[3], &[2, 3])
+    }
+
+    pub fn parse(rulestring: &str) -> Result<Rule, RuleParseError> {
+        let trimmed = rulestring.trim();
+        if trimmed.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let sections: Vec<(usize, &str)> = split_sections(trimmed);
+        if sections.len() > 2 {
+            return Err(RuleParseError::TooManySections);
+        }
+
+        let starts_with_letter = sections
+            .iter()
+            .any(|(_, section)| section.starts_with(|c: char| c.is_ascii_alphabetic()));
+
+        if !starts_with_letter {
+            // S/B notation: "23/3"
+            if sections.len() != 2 {
+                return Err(RuleParseError::MissingBirth);
+            }
+            let (survival_position, survival) = sections[0];
+            let (birth_position, birth) = sections[1];
+            return Ok(Rule {
+                birth: parse_counts(birth, birth_position)?,
+                survival: parse_counts(survival, survival_position)?,
+            });
+        }
+
+        // B/S notation, in either order: "B3/S23", "S23/B3", "B3S23"
+        let mut birth = None;
+        let mut survival = None;
+        for (position, section) in sections {
+            let mut chars = section.chars();
+            let prefix = chars.next().unwrap_or(' ');
+            let counts = parse_counts(chars.as_str(), position + prefix.len_utf8())?;
+            let target = match prefix.to_ascii_uppercase() {
+                'B' => &mut birth,
+                'S' => &mut survival,
+                _ => {
+                    return Err(RuleParseError::InvalidCharacter {
+                        character: prefix,
+                        position,
+                    })
+                }
+            };
+            if target.is_some() {
+                return Err(RuleParseError::TooManySections);
+            }
+            *target = Some(counts);
+        }
+
+        Ok(Rule {
+            birth: birth.ok_or(RuleParseError::MissingBirth)?,
+            survival: survival.ok_or(RuleParseError::MissingSurvival)?,
+        })
+    }
+
+    pub fn is_birth(&self, live_neighbors: u8) -> bool {
+        self.birth & (1 << live_neighbors) != 0
+    }
+
+    pub fn is_survival(&self, live_neighbors: u8) -> bool {
+        self.survival & (1 << live_neighbors) != 0
+    }
+
+    /// The state of a cell in the next generation.
+    pub fn next_state(&self, alive: bool, live_neighbors: u8) -> bool {
+        if alive {
+            self.is_survival(live_neighbors)
+        } else {
+            self.is_birth(live_neighbors)
+        }
+    }
+
+    fn mask(counts: &[u8]) -> u16 {
+        counts
+            .iter()
+            .filter(|count| **count <= 8)
+            .fold(0, |mask, count| mask | (1 << count))
+    }
+}
+
+// Splits a rulestring into its birth and survival sections, remembering where
+// each one starts so that errors can point at the offending character.
+fn split_sections(rulestring: &str) -> Vec<(usize, &str)> {
+    if rulestring.contains('/') {
+        let mut position = 0;
+        return rulestring
+            .split('/')
+            .map(|section| {
+                let start = position;
+                position += section.len() + 1;
+                (start, section)
+            })
+            .collect();
+    }
+
+    // "B3S23": split before the second letter
+    match rulestring
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| c.is_ascii_alphabetic())
+    {
+        Some((split, _)) => vec![(0, &rulestring[..split]), (split, &rulestring[split..])],
+        None => vec![(0, rulestring)],
+    }
+}
+
+fn parse_counts(counts: &str, position: usize) -> Result<u16, RuleParseError> {
+    let mut mask = 0u16;
+    for (offset, c) in counts.char_indices() {
+        let position = position + offset;
+        let count = match c.to_digit(10) {
+            Some(count) if count <= 8 => count,
+            Some(_) => return Err(RuleParseError::InvalidNeighborCount { count: c, position }),
+            None => return Err(RuleParseError::InvalidCharacter { character: c, position }),
+        };
+        if mask & (1 << count) != 0 {
+            return Err(RuleParseError::DuplicateNeighborCount { count: c, position });
+        }
+        mask |= 1 << count;
+    }
+    Ok(mask)
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Rule, RuleParseError> {
+        Rule::parse(s)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for count in (0..=8).filter(|count| self.is_birth(*count)) {
+            write!(f, "{}", count)?;
+        }
+        write!(f, "/S")?;
+        for count in (0..=8).filter(|count| self.is_survival(*count)) {
+            write!(f, "{}", count)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rule({})", self)
+    }
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleParseError::Empty => write!(f, "the rulestring is empty"),
+            RuleParseError::MissingBirth => write!(f, "the rulestring has no birth (B) section"),
+            RuleParseError::MissingSurvival => {
+                write!(f, "the rulestring has no survival (S) section")
+            }
+            RuleParseError::TooManySections => {
+                write!(f, "the rulestring must have exactly one birth and one survival section")
+            }
+            RuleParseError::InvalidCharacter { character, position } => write!(
+                f,
+                "unexpected character '{}' at position {}",
+                character, position
+            ),
+            RuleParseError::InvalidNeighborCount { count, position } => write!(
+                f,
+                "neighbour count {} at position {} is out of range (0-8)",
+                count, position
+            ),
+            RuleParseError::DuplicateNeighborCount { count, position } => write!(
+                f,
+                "neighbour count {} is repeated at position {}",
+                count, position
+            ),
+        }
+    }
+}
+
+impl Error for RuleParseError {}
+
+// Rules are written the way people write them, such as "B3/S23".
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Rule, D::Error> {
+        let rule = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Rule::parse(&rule).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::bitwise;
+use crate::engine::{Engine, EngineError, Grid};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+const CHUNK_SIZE: i64 = 64;
+
+// One u64 per row; bit n is column n.
+type Chunk = [u64; CHUNK_SIZE as usize];
+
+/// An unbounded universe with signed (row, column) coordinates. Only the
+/// 64×64 chunks that contain live cells are stored, so patterns can travel
+/// forever; chunks are allocated as patterns move into them and freed when
+/// they empty out.
+pub struct SparseUniverse {
+    rule: Rule,
+    chunks: HashMap<(i64, i64), Chunk>,
+    generation: i64,
+}
+
+impl SparseUniverse {
+    /// Fails if the rule gives birth to cells with no live neighbours, as
+    /// that would fill the infinite plane.
+    pub fn new(rule: Rule) -> Result<SparseUniverse, EngineError> {
+        if rule.is_birth(0) {
+            return Err(EngineError::UnsupportedRule(rule));
+        }
+
+        Ok(SparseUniverse {
+            rule,
+            chunks: HashMap::new(),
+            generation: 0,
+        })
+    }
+
+    /// Builds a universe from a pattern, using the pattern's rule or B3/S23
+    /// if it has none. The pattern's top-left corner is placed at (0, 0).
+    pub fn from_pattern(pattern: &Pattern) -> Result<SparseUniverse, EngineError> {
+        let mut universe = SparseUniverse::new(pattern.rule.unwrap_or_default())?;
+        universe.load_pattern(pattern, 0, 0);
+        Ok(universe)
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn generation(&self) -> i64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.chunks
+            .values()
+            .flat_map(|chunk| chunk.iter())
+            .map(|row| row.count_ones() as u64)
+            .sum()
+    }
+
+    /// The number of chunks currently allocated.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn cell_at(&self, row: i64, column: i64) -> bool {
+        let (key, local_row, local_column) = locate(row, column);
+        self.chunks
+            .get(&key)
+            .is_some_and(|chunk| chunk[local_row] & (1 << local_column) != 0)
+    }
+
+    pub fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        let (key, local_row, local_column) = locate(row, column);
+        if alive {
+            let chunk = self.chunks.entry(key).or_insert([0; CHUNK_SIZE as usize]);
+            chunk[local_row] |= 1 << local_column;
+        } else if let Some(chunk) = self.chunks.get_mut(&key) {
+            chunk[local_row] &= !(1 << local_column);
+            if chunk.iter().all(|row| *row == 0) {
+                self.chunks.remove(&key);
+            }
+        }
+    }
+
+    pub fn toggle_cell(&mut self, row: i64, column: i64) {
+        let alive = self.cell_at(row, column);
+        self.set_cell(row, column, !alive);
+    }
+
+    /// Kills every cell and starts counting generations from 0 again.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.generation = 0;
+    }
+
+    /// Places a pattern's live cells with its top-left corner at (row, column).
+    pub fn load_pattern(&mut self, pattern: &Pattern, row: i64, column: i64) {
+        for (pattern_row, pattern_column) in pattern.live_cells() {
+            self.set_cell(row + pattern_row as i64, column + pattern_column as i64, true);
+        }
+    }
+
+    /// Every live cell, in no particular order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.chunks.iter().flat_map(|((chunk_row, chunk_column), chunk)| {
+            chunk.iter().enumerate().flat_map(move |(local_row, bits)| {
+                (0..CHUNK_SIZE)
+                    .filter(move |local_column| bits & (1 << local_column) != 0)
+                    .map(move |local_column| {
+                        (chunk_row * CHUNK_SIZE + local_row as i64, chunk_column * CHUNK_SIZE + local_column)
+                    })
+            })
+        })
+    }
+
+    /// The live cells within a viewport, such as the part of the universe a
+    /// renderer is showing. Only the chunks that overlap it are visited.
+    pub fn live_cells_in(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<(i64, i64)> {
+        let bottom = top + height as i64;
+        let right = left + width as i64;
+        let mut cells = Vec::new();
+
+        for chunk_row in top.div_euclid(CHUNK_SIZE)..=(bottom - 1).div_euclid(CHUNK_SIZE) {
+            for chunk_column in left.div_euclid(CHUNK_SIZE)..=(right - 1).div_euclid(CHUNK_SIZE) {
+                let chunk = match self.chunks.get(&(chunk_row, chunk_column)) {
+                    Some(chunk) => chunk,
+                    None => continue,
+                };
+
+                for (local_row, bits) in chunk.iter().enumerate() {
+                    let row = chunk_row * CHUNK_SIZE + local_row as i64;
+                    if *bits == 0 || row < top || row >= bottom {
+                        continue;
+                    }
+                    for local_column in 0..CHUNK_SIZE {
+                        let column = chunk_column * CHUNK_SIZE + local_column;
+                        if bits & (1 << local_column) != 0 && column >= left && column < right {
+                            cells.push((row, column));
+                        }
+                    }
+                }
+            }
+        }
+
+        cells
+    }
+
+    pub fn tick(&mut self) {
+        let births: Vec<u8> = (0..=8).filter(|count| self.rule.is_birth(*count)).collect();
+        let survivals: Vec<u8> = (0..=8).filter(|count| self.rule.is_survival(*count)).collect();
+
+        // Cells can only be born next to live cells, so the only chunks that
+        // can change are the live ones and their neighbours.
+        let mut candidates = HashSet::new();
+        for (chunk_row, chunk_column) in self.chunks.keys() {
+            for delta_row in -1..=1 {
+                for delta_column in -1..=1 {
+                    candidates.insert((chunk_row + delta_row, chunk_column + delta_column));
+                }
+            }
+        }
+
+        let mut next_chunks = HashMap::with_capacity(candidates.len());
+        for key in candidates {
+            let chunk = self.next_chunk(key, &births, &survivals);
+            if chunk.iter().any(|row| *row != 0) {
+                next_chunks.insert(key, chunk);
+            }
+        }
+
+        self.chunks = next_chunks;
+        self.generation += 1;
+    }
+
+    fn next_chunk(&self, (chunk_row, chunk_column): (i64, i64), births: &[u8], survivals: &[u8]) -> Chunk {
+        const EMPTY: Chunk = [0; CHUNK_SIZE as usize];
+        let neighborhood: Vec<&Chunk> = (-1..=1)
+            .flat_map(|delta_row| (-1..=1).map(move |delta_column| (delta_row, delta_column)))
+            .map(|(delta_row, delta_column)| {
+                self.chunks
+                    .get(&(chunk_row + delta_row, chunk_column + delta_column))
+                    .unwrap_or(&EMPTY)
+            })
+            .collect();
+
+        // The words for a row of this chunk, and the bordering words to its
+        // left and right, where row can be -1 or 64.
+        let row_words = |row: i64| -> (u64, u64, u64) {
+            let vertical = (row.div_euclid(CHUNK_SIZE) + 1) as usize;
+            let local_row = row.rem_euclid(CHUNK_SIZE) as usize;
+            (
+                neighborhood[vertical * 3][local_row],
+                neighborhood[vertical * 3 + 1][local_row],
+                neighborhood[vertical * 3 + 2][local_row],
+            )
+        };
+
+        let mut next = EMPTY;
+        for (row, next_row) in next.iter_mut().enumerate() {
+            let mut count = [0u64; 4];
+            for delta_row in -1..=1 {
+                let (left, center, right) = row_words(row as i64 + delta_row);
+                bitwise::add(&mut count, (center << 1) | (left >> 63));
+                bitwise::add(&mut count, (center >> 1) | (right << 63));
+                if delta_row != 0 {
+                    bitwise::add(&mut count, center);
+                }
+            }
+
+            let (_, alive, _) = row_words(row as i64);
+            *next_row = bitwise::apply_rule(&count, alive, births, survivals);
+        }
+        next
+    }
+}
+
+impl Engine for SparseUniverse {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn generation(&self) -> i64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        SparseUniverse::population(self)
+    }
+
+    fn tick(&mut self) {
+        SparseUniverse::tick(self);
+    }
+
+    fn to_pattern(&self) -> Result<Pattern, EngineError> {
+        let cells: Vec<(i64, i64)> = self.live_cells().collect();
+        let mut pattern = Pattern::from_signed_cells(&cells, 0).map_err(|_| EngineError::TooLarge)?;
+        pattern.rule = Some(self.rule);
+        Ok(pattern)
+    }
+}
+
+impl Grid for SparseUniverse {
+    fn size(&self) -> Option<(u32, u32)> {
+        None
+    }
+
+    fn cell_at(&self, row: i64, column: i64) -> bool {
+        SparseUniverse::cell_at(self, row, column)
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        SparseUniverse::set_cell(self, row, column, alive);
+    }
+
+    fn live_cells_in(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<(i64, i64)> {
+        SparseUniverse::live_cells_in(self, top, left, height, width)
+    }
+
+    fn clear(&mut self) {
+        SparseUniverse::clear(self);
+    }
+}
+
+// The chunk holding a cell, and the cell's row and column within it.
+fn locate(row: i64, column: i64) -> ((i64, i64), usize, usize) {
+    (
+        (row.div_euclid(CHUNK_SIZE), column.div_euclid(CHUNK_SIZE)),
+        row.rem_euclid(CHUNK_SIZE) as usize,
+        column.rem_euclid(CHUNK_SIZE) as usize,
+    )
+}
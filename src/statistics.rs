@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use crate::rect::Rect;
+
+/// Measurements of one generation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenerationStats {
+    pub generation: i64,
+    pub population: u64,
+    /// Cells that came to life since the previous generation.
+    pub births: u64,
+    /// Cells that died since the previous generation.
+    pub deaths: u64,
+    /// The smallest rectangle containing every live cell, or None if there
+    /// are none.
+    pub bounding_box: Option<Rect>,
+    /// The fraction of the universe that's alive, from 0 to 1.
+    pub density: f64,
+}
+
+/// The statistics of the most recent generations, up to a fixed capacity;
+/// once it's full, recording a generation forgets the oldest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    capacity: usize,
+    samples: VecDeque<GenerationStats>,
+}
+
+impl Statistics {
+    pub fn new(capacity: usize) -> Statistics {
+        Statistics {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn push(&mut self, stats: GenerationStats) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(stats);
+    }
+
+    /// Forgets every generation from `generation` on, such as after going
+    /// back in time.
+    pub fn truncate_from(&mut self, generation: i64) {
+        while self.samples.back().is_some_and(|stats| stats.generation >= generation) {
+            self.samples.pop_back();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The recorded generations, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &GenerationStats> + '_ {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&GenerationStats> {
+        self.samples.back()
+    }
+
+    /// One series, oldest first, such as `statistics.series(|stats| stats.population)`.
+    pub fn series<T, F>(&self, measure: F) -> Vec<T>
+    where
+        F: Fn(&GenerationStats) -> T,
+    {
+        self.samples.iter().map(measure).collect()
+    }
+
+    /// A CSV table with a header row and one row per generation. The
+    /// bounding box columns are empty when nothing is alive.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("generation,population,births,deaths,top,left,width,height,density\n");
+        for stats in self.samples.iter() {
+            let bounding_box = match stats.bounding_box {
+                Some(rect) => format!("{},{},{},{}", rect.row, rect.column, rect.width, rect.height),
+                None => String::from(",,,"),
+            };
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                stats.generation, stats.population, stats.births, stats.deaths, bounding_box, stats.density
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// A JSON array with one object per generation.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (index, stats) in self.samples.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let bounding_box = match stats.bounding_box {
+                Some(rect) => format!(
+                    "{{\"top\":{},\"left\":{},\"width\":{},\"height\":{}}}",
+                    rect.row, rect.column, rect.width, rect.height
+                ),
+                None => String::from("null"),
+            };
+            write!(
+                json,
+                "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\"bounding_box\":{},\"density\":{}}}",
+                stats.generation, stats.population, stats.births, stats.deaths, bounding_box, stats.density
+            )
+            .unwrap();
+        }
+        json.push(']');
+        json
+    }
+}
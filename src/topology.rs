@@ -0,0 +1,79 @@
+/// How the edges of a finite universe are joined together.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "web", wasm_bindgen::prelude::wasm_bindgen)]
+pub enum Topology {
+    /// Both pairs of opposite edges are joined, so patterns wrap around.
+    #[default]
+    Torus,
+
+    /// Nothing is joined; every cell outside the grid is permanently dead.
+    Bounded,
+
+    /// The left and right edges are joined as on a torus, while crossing the
+    /// top or bottom edge mirrors the column.
+    KleinBottle,
+
+    /// Both pairs of edges are joined with a twist: crossing the left or right
+    /// edge mirrors the row, and crossing the top or bottom edge mirrors the
+    /// column.
+    CrossSurface,
+}
+
+impl Topology {
+    /// Maps a possibly out-of-range coordinate onto the grid, or returns
+    /// `None` if it lies outside a bounded universe.
+    pub fn wrap(&self, row: i64, column: i64, width: u32, height: u32) -> Option<(u32, u32)> {
+        self.wrap_mirrored(row, column, width, height).map(|(cell, _)| cell)
+    }
+
+    // Like `wrap`, along with whether the row and the column ended up
+    // mirrored. Past a mirrored edge, moving down (or right) from the mapped
+    // cell goes the way moving up (or left) did before crossing it.
+    pub(crate) fn wrap_mirrored(
+        &self,
+        row: i64,
+        column: i64,
+        width: u32,
+        height: u32,
+    ) -> Option<((u32, u32), (bool, bool))> {
+        let width = width as i64;
+        let height = height as i64;
+
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let (row, column, mirrored) = match self {
+            Topology::Torus => (row.rem_euclid(height), column.rem_euclid(width), (false, false)),
+            Topology::Bounded => {
+                if row < 0 || row >= height || column < 0 || column >= width {
+                    return None;
+                }
+                (row, column, (false, false))
+            }
+            Topology::KleinBottle => {
+                let (column, column_mirrored) = twist(column, row.div_euclid(height), width);
+                (row.rem_euclid(height), column.rem_euclid(width), (false, column_mirrored))
+            }
+            Topology::CrossSurface => {
+                let (row, row_mirrored) = twist(row, column.div_euclid(width), height);
+                let column = column.rem_euclid(width);
+                let (column, column_mirrored) = twist(column, row.div_euclid(height), width);
+                (row.rem_euclid(height), column, (row_mirrored, column_mirrored))
+            }
+        };
+
+        Some(((row as u32, column as u32), mirrored))
+    }
+}
+
+// Mirrors a coordinate once for every time its partner crossed an edge,
+// returning it and whether it ended up mirrored.
+fn twist(coordinate: i64, crossings: i64, size: i64) -> (i64, bool) {
+    if crossings % 2 == 0 {
+        (coordinate, false)
+    } else {
+        (size - 1 - coordinate, true)
+    }
+}
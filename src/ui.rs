@@ -1,34 +1,49 @@
-// TODO: There's probably a better way to do this
-#[path = "universe.rs"] mod universe;
-#[path = "web_sys_mixins.rs"] mod web_sys_mixins;
-
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
 
 extern crate web_sys;
+use web_sys::Blob;
+use web_sys::BlobPropertyBag;
 use web_sys::CanvasRenderingContext2d;
+use web_sys::ClipboardEvent;
+use web_sys::Document;
 use web_sys::Element;
+use web_sys::HtmlAnchorElement;
 use web_sys::HtmlCanvasElement;
 use web_sys::HtmlElement;
 use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
+use web_sys::KeyboardEvent;
 use web_sys::MouseEvent;
+use web_sys::Url;
 use web_sys::Window;
-use wasm_bindgen::JsValue;
 
 use std::cell::RefCell;
+use std::cmp::max;
 use std::cmp::min;
 use std::rc::Rc;
 use std::rc::Weak;
 
-use universe::Universe;
-use web_sys_mixins::AnimationFrameRequester;
-use web_sys_mixins::HtmlExt;
-use web_sys_mixins::IntervalSubscription;
-use web_sys_mixins::RegisteredHtmlEvent;
-use web_sys_mixins::WindowExt;
+use crate::changes::ChangeSet;
+use crate::cycle::AutoPause;
+use crate::image::{write_png, Color, GifWriter, ImageStyle};
+use crate::library::LIBRARY;
+use crate::pattern::{parse_pattern, Pattern, Rotation};
+use crate::rect::Rect;
+use crate::rle::write_rle;
+use crate::universe::Universe;
+use crate::web_sys_mixins::AnimationFrameRequester;
+use crate::web_sys_mixins::HtmlExt;
+use crate::web_sys_mixins::IntervalSubscription;
+use crate::web_sys_mixins::RegisteredHtmlEvent;
+use crate::web_sys_mixins::WindowExt;
 
 const CELL_SIZE: u32 = 10; // px
 const WIDTH: u32 = 96;
 const HEIGHT: u32 = 64;
+const HISTORY_BUDGET: usize = 16 * 1024 * 1024; // bytes
+const GIF_BUDGET: usize = 64 * 1024 * 1024; // bytes
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -42,25 +57,73 @@ thread_local! {
 }
 
 struct Constants {
-	grid_color: JsValue,
-	dead_color: JsValue,
-	alive_color: JsValue,
+	grid_color: &'static str,
+	dead_color: &'static str,
+	alive_color: &'static str,
+	selection_color: &'static str,
+	paste_color: &'static str,
 }
 
+// Called with the system clipboard's text, or with the error if it couldn't
+// be read
+type ClipboardReadClosures = (Closure<dyn FnMut(JsValue)>, Closure<dyn FnMut(JsValue)>);
+
 pub struct Ui {
 	welf: Option<Weak<RefCell<Ui>>>,
 	window: Window,
+	document: Document,
 	canvas_element: Element,
 	canvas: HtmlCanvasElement,
 	context: CanvasRenderingContext2d,
 	play_pause_button: HtmlElement,
 	clear_button: HtmlElement,
 	randomize_button: HtmlElement,
+	step_back_button: HtmlElement,
+	undo_button: HtmlElement,
+	redo_button: HtmlElement,
+	copy_button: HtmlElement,
+	cut_button: HtmlElement,
+	paste_button: HtmlElement,
+	save_png_button: HtmlElement,
+	record_gif_button: HtmlElement,
 	ticks_per_second_input: HtmlInputElement,
+	seed_input: HtmlInputElement,
+	pattern_select: HtmlSelectElement,
+	rotate_button: HtmlElement,
+	mirror_input: HtmlInputElement,
+	rotation: Rotation,
+	auto_pause_input: HtmlInputElement,
+	auto_pause: AutoPause,
+	stability_element: Element,
 	animation_id: Option<i32>,
 	drawn_generation: i64,
 	universe: Universe,
 	
+	// Cells that changed since they were last drawn
+	dirty: Option<ChangeSet>,
+	
+	selection: Option<Rect>,
+	
+	// Where the mouse went down, and whether it has moved to another cell
+	// since, which makes it a drag that selects rather than a click
+	drag_start: Option<(u32, u32)>,
+	dragged: bool,
+	
+	clipboard: Option<Pattern>,
+	
+	// A pattern being pasted, floating over the universe with its top-left
+	// corner at (row, column) until a click puts it down
+	paste: Option<(Pattern, u32, u32)>,
+	
+	clipboard_read_closures: Option<ClipboardReadClosures>,
+	
+	// Every generation since recording started
+	gif: Option<GifWriter>,
+	
+	// The last file downloaded, kept until the next one as the browser may
+	// still be reading it
+	download_url: Option<String>,
+	
 	animation_frame_requester: Option<AnimationFrameRequester>,
 	
 	timer: Option<IntervalSubscription>,
@@ -74,20 +137,76 @@ pub struct Ui {
 	#[allow(dead_code)]
 	randomize_button_event: Option<RegisteredHtmlEvent<'static>>,
 	
+	#[allow(dead_code)]
+	step_back_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	undo_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	redo_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	copy_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	cut_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	paste_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	save_png_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	record_gif_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	seed_input_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	rotate_button_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	mirror_input_event: Option<RegisteredHtmlEvent<'static>>,
+	
 	#[allow(dead_code)]
 	ticks_per_second_input_event: Option<RegisteredHtmlEvent<'static>>,
 	
 	#[allow(dead_code)]
-	canvas_click_event: Option<RegisteredHtmlEvent<'static>>
+	canvas_click_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	canvas_mouse_down_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	canvas_mouse_move_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	canvas_mouse_up_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	key_down_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	copy_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	cut_event: Option<RegisteredHtmlEvent<'static>>,
+	
+	#[allow(dead_code)]
+	paste_event: Option<RegisteredHtmlEvent<'static>>
 }
 
 
 impl Constants {
     pub fn new() -> Constants {
     	Constants {
-			grid_color: JsValue::from_str("#CCCCCC"),
-			dead_color: JsValue::from_str("#FFFFFF"),
-			alive_color: JsValue::from_str("#000000"),
+			grid_color: "#CCCCCC",
+			dead_color: "#FFFFFF",
+			alive_color: "#000000",
+			selection_color: "rgba(0, 120, 255, 0.3)",
+			paste_color: "rgba(0, 120, 255, 0.8)",
     	}
 	}
 }
@@ -118,6 +237,15 @@ impl Ui {
 			animation_id: None,
 			drawn_generation: -1,
 			universe: Universe::new(WIDTH, HEIGHT),
+			dirty: None,
+			selection: None,
+			drag_start: None,
+			dragged: false,
+			clipboard: None,
+			paste: None,
+			clipboard_read_closures: None,
+			gif: None,
+			download_url: None,
 			timer: None,
 			
     		play_pause_button: document.get_element_by_id("play-pause")
@@ -135,64 +263,274 @@ impl Ui {
     			.dyn_into::<web_sys::HtmlElement>()
 	    		.expect("Expected a button"),
 
+    		step_back_button: document.get_element_by_id("step-back")
+    			.expect("Could not get the step back button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		undo_button: document.get_element_by_id("undo")
+    			.expect("Could not get the undo button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		redo_button: document.get_element_by_id("redo")
+    			.expect("Could not get the redo button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		copy_button: document.get_element_by_id("copy")
+    			.expect("Could not get the copy button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		cut_button: document.get_element_by_id("cut")
+    			.expect("Could not get the cut button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		paste_button: document.get_element_by_id("paste")
+    			.expect("Could not get the paste button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		save_png_button: document.get_element_by_id("save-png")
+    			.expect("Could not get the save PNG button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		record_gif_button: document.get_element_by_id("record-gif")
+    			.expect("Could not get the record GIF button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
     		ticks_per_second_input: document.get_element_by_id("tickspersecond")
     			.expect("Could not get the ticks per second slider")
     			.dyn_into::<web_sys::HtmlInputElement>()
 	    		.expect("Expected a slider"),
+
+    		seed_input: document.get_element_by_id("seed")
+    			.expect("Could not get the seed input")
+    			.dyn_into::<web_sys::HtmlInputElement>()
+	    		.expect("Expected an input"),
+
+    		pattern_select: document.get_element_by_id("pattern")
+    			.expect("Could not get the pattern list")
+    			.dyn_into::<web_sys::HtmlSelectElement>()
+	    		.expect("Expected a select"),
+
+    		rotate_button: document.get_element_by_id("rotate")
+    			.expect("Could not get the rotate button")
+    			.dyn_into::<web_sys::HtmlElement>()
+	    		.expect("Expected a button"),
+
+    		mirror_input: document.get_element_by_id("mirror")
+    			.expect("Could not get the mirror checkbox")
+    			.dyn_into::<web_sys::HtmlInputElement>()
+	    		.expect("Expected a checkbox"),
+    		rotation: Rotation::None,
+
+    		auto_pause_input: document.get_element_by_id("auto-pause")
+    			.expect("Could not get the auto-pause checkbox")
+    			.dyn_into::<web_sys::HtmlInputElement>()
+	    		.expect("Expected a checkbox"),
+    		auto_pause: AutoPause::new(),
+
+    		stability_element: document.get_element_by_id("stability")
+    			.expect("Could not get the stability element"),
     		
     		canvas_element,
     		canvas,
     		window,
+    		document: document.clone(),
 
     		welf: None,
 			animation_frame_requester: None,
 			play_pause_button_event: None,
 			clear_button_event: None,
 			randomize_button_event: None,
+			step_back_button_event: None,
+			undo_button_event: None,
+			redo_button_event: None,
+			copy_button_event: None,
+			cut_button_event: None,
+			paste_button_event: None,
+			save_png_button_event: None,
+			record_gif_button_event: None,
+			seed_input_event: None,
+			rotate_button_event: None,
+			mirror_input_event: None,
 			ticks_per_second_input_event: None,
     		canvas_click_event: None,
+    		canvas_mouse_down_event: None,
+    		canvas_mouse_move_event: None,
+    		canvas_mouse_up_event: None,
+    		key_down_event: None,
+    		copy_event: None,
+    		cut_event: None,
+    		paste_event: None,
     	}));
 
 		{
 			// TODO: Rename *_s to welf
 			let mut welf = self_rc.borrow_mut();
 
-			(*welf).welf = Some(Rc::downgrade(&self_rc));		
+			welf.welf = Some(Rc::downgrade(&self_rc));		
 		
     	
 			let animation_s = Rc::downgrade(&self_rc);
-			(*welf).animation_frame_requester = Some((*welf).window.prepare_animation_frame_callback(Box::new(move |_| {
+			welf.animation_frame_requester = Some(welf.window.prepare_animation_frame_callback(Box::new(move |_| {
 				(*(animation_s.upgrade().unwrap().borrow_mut())).render_loop();
 			})));
 
     		let play_pause_button_s = Rc::downgrade(&self_rc);
-			(*welf).play_pause_button_event = Some((*welf).play_pause_button.events().add_event_listener("click", Box::new(move |_| {
+			welf.play_pause_button_event = Some(welf.play_pause_button.events().add_event_listener("click", Box::new(move |_| {
 				(*(play_pause_button_s.upgrade().unwrap().borrow_mut())).play_pause();
 			})).unwrap());
 
     		let clear_button_s = Rc::downgrade(&self_rc);
-   			(*welf).clear_button_event = Some((*welf).clear_button.events().add_event_listener("click", Box::new(move |_| {
+   			welf.clear_button_event = Some(welf.clear_button.events().add_event_listener("click", Box::new(move |_| {
 				(*(clear_button_s.upgrade().unwrap().borrow_mut())).clear();
 	   		})).unwrap());
     		    		
 	    	let randomize_button_s = Rc::downgrade(&self_rc);
-			(*welf).randomize_button_event = Some((*welf).randomize_button.events().add_event_listener("click", Box::new(move |_| {
+			welf.randomize_button_event = Some(welf.randomize_button.events().add_event_listener("click", Box::new(move |_| {
 				(*(randomize_button_s.upgrade().unwrap().borrow_mut())).randomize();
 			})).unwrap());
+
+	    	let step_back_button_s = Rc::downgrade(&self_rc);
+			welf.step_back_button_event = Some(welf.step_back_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(step_back_button_s.upgrade().unwrap().borrow_mut())).step_back();
+			})).unwrap());
+
+	    	let undo_button_s = Rc::downgrade(&self_rc);
+			welf.undo_button_event = Some(welf.undo_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(undo_button_s.upgrade().unwrap().borrow_mut())).undo();
+			})).unwrap());
+
+	    	let redo_button_s = Rc::downgrade(&self_rc);
+			welf.redo_button_event = Some(welf.redo_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(redo_button_s.upgrade().unwrap().borrow_mut())).redo();
+			})).unwrap());
+
+	    	let copy_button_s = Rc::downgrade(&self_rc);
+			welf.copy_button_event = Some(welf.copy_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(copy_button_s.upgrade().unwrap().borrow_mut())).copy_to_system_clipboard(false);
+			})).unwrap());
+
+	    	let cut_button_s = Rc::downgrade(&self_rc);
+			welf.cut_button_event = Some(welf.cut_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(cut_button_s.upgrade().unwrap().borrow_mut())).copy_to_system_clipboard(true);
+			})).unwrap());
+
+	    	let paste_button_s = Rc::downgrade(&self_rc);
+			welf.paste_button_event = Some(welf.paste_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(paste_button_s.upgrade().unwrap().borrow_mut())).paste_from_system_clipboard();
+			})).unwrap());
+
+	    	let save_png_button_s = Rc::downgrade(&self_rc);
+			welf.save_png_button_event = Some(welf.save_png_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(save_png_button_s.upgrade().unwrap().borrow_mut())).save_png();
+			})).unwrap());
+
+	    	let record_gif_button_s = Rc::downgrade(&self_rc);
+			welf.record_gif_button_event = Some(welf.record_gif_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(record_gif_button_s.upgrade().unwrap().borrow_mut())).record_gif();
+			})).unwrap());
+
+			let read_s = Rc::downgrade(&self_rc);
+			let read_failed_s = Rc::downgrade(&self_rc);
+			welf.clipboard_read_closures = Some((
+				Closure::wrap(Box::new(move |text: JsValue| {
+					(*(read_s.upgrade().unwrap().borrow_mut())).begin_paste(text.as_string());
+				}) as Box<dyn FnMut(JsValue)>),
+				Closure::wrap(Box::new(move |_| {
+					(*(read_failed_s.upgrade().unwrap().borrow_mut())).begin_paste(None);
+				}) as Box<dyn FnMut(JsValue)>)));
+
+	    	let seed_input_s = Rc::downgrade(&self_rc);
+			welf.seed_input_event = Some(welf.seed_input.events().add_event_listener("change", Box::new(move |_| {
+				(*(seed_input_s.upgrade().unwrap().borrow_mut())).randomize_from_seed_input();
+			})).unwrap());
+
+	    	let rotate_button_s = Rc::downgrade(&self_rc);
+			welf.rotate_button_event = Some(welf.rotate_button.events().add_event_listener("click", Box::new(move |_| {
+				(*(rotate_button_s.upgrade().unwrap().borrow_mut())).rotate();
+			})).unwrap());
+
+	    	let mirror_input_s = Rc::downgrade(&self_rc);
+			welf.mirror_input_event = Some(welf.mirror_input.events().add_event_listener("change", Box::new(move |_| {
+				(*(mirror_input_s.upgrade().unwrap().borrow_mut())).draw_cells();
+			})).unwrap());
 			
     		let ticks_per_second_s = Rc::downgrade(&self_rc);
-			(*welf).ticks_per_second_input_event = Some((*welf).ticks_per_second_input.events().add_event_listener("click", Box::new(move |_| {
+			welf.ticks_per_second_input_event = Some(welf.ticks_per_second_input.events().add_event_listener("click", Box::new(move |_| {
 				(*(ticks_per_second_s.upgrade().unwrap().borrow_mut())).update_ticks_per_second();
 			})).unwrap());
 
     		let canvas_s = Rc::downgrade(&self_rc);
-    		(*welf).canvas_click_event = Some((*welf).canvas.events().add_event_listener("click", Box::new(move |event| {
+    		welf.canvas_click_event = Some(welf.canvas.events().add_event_listener("click", Box::new(move |event| {
 				let mouse_event = event.dyn_into::<MouseEvent>().unwrap();
 				(*(canvas_s.upgrade().unwrap().borrow_mut())).canvas_click(mouse_event);
 			})).unwrap());
+
+    		let canvas_mouse_down_s = Rc::downgrade(&self_rc);
+    		welf.canvas_mouse_down_event = Some(welf.canvas.events().add_event_listener("mousedown", Box::new(move |event| {
+				let mouse_event = event.dyn_into::<MouseEvent>().unwrap();
+				(*(canvas_mouse_down_s.upgrade().unwrap().borrow_mut())).canvas_mouse_down(mouse_event);
+			})).unwrap());
+
+    		let canvas_mouse_move_s = Rc::downgrade(&self_rc);
+    		welf.canvas_mouse_move_event = Some(welf.canvas.events().add_event_listener("mousemove", Box::new(move |event| {
+				let mouse_event = event.dyn_into::<MouseEvent>().unwrap();
+				(*(canvas_mouse_move_s.upgrade().unwrap().borrow_mut())).canvas_mouse_move(mouse_event);
+			})).unwrap());
+
+    		let canvas_mouse_up_s = Rc::downgrade(&self_rc);
+    		welf.canvas_mouse_up_event = Some(welf.canvas.events().add_event_listener("mouseup", Box::new(move |_| {
+				canvas_mouse_up_s.upgrade().unwrap().borrow_mut().drag_start = None;
+			})).unwrap());
+
+    		let key_down_s = Rc::downgrade(&self_rc);
+    		welf.key_down_event = Some(welf.document.events().add_event_listener("keydown", Box::new(move |event| {
+				let keyboard_event = event.dyn_into::<KeyboardEvent>().unwrap();
+				(*(key_down_s.upgrade().unwrap().borrow_mut())).key_down(keyboard_event);
+			})).unwrap());
+
+    		let copy_s = Rc::downgrade(&self_rc);
+    		welf.copy_event = Some(welf.document.events().add_event_listener("copy", Box::new(move |event| {
+				let clipboard_event = event.dyn_into::<ClipboardEvent>().unwrap();
+				(*(copy_s.upgrade().unwrap().borrow_mut())).copy_event(clipboard_event, false);
+			})).unwrap());
+
+    		let cut_s = Rc::downgrade(&self_rc);
+    		welf.cut_event = Some(welf.document.events().add_event_listener("cut", Box::new(move |event| {
+				let clipboard_event = event.dyn_into::<ClipboardEvent>().unwrap();
+				(*(cut_s.upgrade().unwrap().borrow_mut())).copy_event(clipboard_event, true);
+			})).unwrap());
+
+    		let paste_s = Rc::downgrade(&self_rc);
+    		welf.paste_event = Some(welf.document.events().add_event_listener("paste", Box::new(move |event| {
+				let clipboard_event = event.dyn_into::<ClipboardEvent>().unwrap();
+				(*(paste_s.upgrade().unwrap().borrow_mut())).paste_event(clipboard_event);
+			})).unwrap());
+    	
+    		for entry in LIBRARY.iter() {
+    			let option = document.create_element("option")
+    				.expect("Could not create an option");
+    			option.set_attribute("value", entry.id)
+    				.expect("Could not set the option's value");
+    			option.set_text_content(Some(entry.name));
+    			welf.pattern_select.append_child(&option)
+    				.expect("Could not add a pattern to the list");
+    		}
+    		welf.rotate_button.set_text_content(Some(rotation_label(welf.rotation)));
     	
-    		(*welf).universe.randomize();
-    		(*welf).pause();
+    		welf.universe.set_change_tracking(true);
+    		welf.universe.set_history_budget(HISTORY_BUDGET);
+    		welf.universe.set_cycle_detection(true);
+    		let seed = welf.universe.randomize();
+    		welf.seed_input.set_value(&seed.to_string());
+    		welf.pause();
     	}
     	
     	self_rc
@@ -200,7 +538,7 @@ impl Ui {
 	
 	pub fn draw_grid(&self) {
 		self.context.begin_path();
-		CONSTANTS.with(|c| self.context.set_stroke_style(&c.grid_color));
+		CONSTANTS.with(|c| self.context.set_stroke_style_str(c.grid_color));
 		
 		// Vertical lines.
 		for i in 0..self.universe.width() {
@@ -218,35 +556,96 @@ impl Ui {
 	}
 	
 	fn draw_cells(&self) {		
+		for row in 0..self.universe.height() {
+   	    	for col in 0..self.universe.width() {
+				self.draw_cell(row, col);
+			}
+       	}
+		self.draw_overlay();
+		self.draw_stability();
+	}
+	
+	fn draw_stability(&self) {
+		if let Some(stability) = self.universe.stability() {
+			self.stability_element.set_text_content(Some(&stability.to_string()));
+		}
+	}
+	
+	// Repaints only the cells that changed, which is much cheaper than
+	// draw_cells() when most of the grid is still.
+	fn draw_changes(&self, changes: &ChangeSet) {
+		for (row, col) in changes.births().chain(changes.deaths()) {
+			self.draw_cell(row, col);
+		}
+		self.draw_overlay();
+	}
+	
+	// Shades the selection and the pattern being pasted. The cells under
+	// them are repainted first, so shading doesn't build up frame to frame.
+	fn draw_overlay(&self) {
 		CONSTANTS.with(|c| {
-			for row in 0..self.universe.height() {
-   		    	for col in 0..self.universe.width() {
-           		    let cell = self.universe.cell_at(row, col);
-                
-               		let fill_style = match cell {
-          				true => &c.alive_color,
-               			false => &c.dead_color
-            		};
-                
-       		        self.context.set_fill_style(&fill_style);
-           		    self.context.fill_rect(
-		    		    (col * (CELL_SIZE + 1) + 1) as f64,
-		        		(row * (CELL_SIZE + 1) + 1) as f64,
-			        	CELL_SIZE as f64,
-				        CELL_SIZE as f64);
+			if let Some(selection) = self.selection {
+				for row in selection.row..min(selection.bottom(), self.universe.height()) {
+					for col in selection.column..min(selection.right(), self.universe.width()) {
+						self.draw_cell(row, col);
+						self.fill_cell(row, col, c.selection_color);
+					}
+				}
+			}
+			
+			if let Some((pattern, top, left)) = &self.paste {
+				let pattern = pattern.transformed(self.rotation, self.mirror_input.checked());
+				let (height, width) = (self.universe.height() as u64, self.universe.width() as u64);
+				for (pattern_row, pattern_col) in pattern.live_cells() {
+					let row = (*top as u64 + pattern_row as u64) % height;
+					let col = (*left as u64 + pattern_col as u64) % width;
+					self.fill_cell(row as u32, col as u32, c.paste_color);
 				}
-       	    }
+			}
+		});
+	}
+	
+	fn draw_cell(&self, row: u32, col: u32) {
+		CONSTANTS.with(|c| {
+       		let fill_style = match self.universe.cell_at(row, col) {
+  				true => c.alive_color,
+       			false => c.dead_color
+    		};
+        
+			self.fill_cell(row, col, fill_style);
         });
 	}
 	
+	fn fill_cell(&self, row: u32, col: u32, fill_style: &str) {
+	    self.context.set_fill_style_str(fill_style);
+		self.context.fill_rect(
+			(col * (CELL_SIZE + 1) + 1) as f64,
+			(row * (CELL_SIZE + 1) + 1) as f64,
+			CELL_SIZE as f64,
+			CELL_SIZE as f64);
+	}
+	
 	fn render_loop(& mut self) {
 		let generation = self.universe.generation();
 
 		if generation != self.drawn_generation {
-			self.draw_cells();
+			match self.dirty.take() {
+				Some(changes) => self.draw_changes(&changes),
+				None => self.draw_cells()
+			}
+			self.draw_stability();
 			self.drawn_generation = generation;
 		}
 		
+		// Pausing from here rather than from tick(), as that runs inside the
+		// timer's own callback. It only happens once each time the universe
+		// settles, so pressing play again lets oscillators and spaceships run.
+		if self.timer.is_some()
+			&& self.auto_pause_input.checked()
+			&& self.auto_pause.should_pause(self.universe.stability(), generation) {
+			self.pause();
+		}
+		
 		if self.timer.is_some() {
 			match &self.animation_frame_requester {
 				Some(animation_frame_requester) => {
@@ -262,21 +661,128 @@ impl Ui {
 	}
 	
 	fn reset_timer(& mut self) {
-		match &self.welf {
-			Some(w) => {
-				let welf = w.clone();
-				let delay = (1.0 / self.ticks_per_second_input.value_as_number() * 1000.0) as i32;
-				let timer = self.window.set_interval(Box::new(move || {
-						(*(welf.upgrade().unwrap().borrow_mut())).universe.tick();
-					}),
-					delay)
-					.expect("Can not set up a timer");
-				self.timer = Some(timer);
+		if let Some(w) = &self.welf {
+			let welf = w.clone();
+			let delay = (1.0 / self.ticks_per_second_input.value_as_number() * 1000.0) as i32;
+			let timer = self.window.set_interval(Box::new(move || {
+					welf.upgrade().unwrap().borrow_mut().tick();
+				}),
+				delay)
+				.expect("Can not set up a timer");
+			self.timer = Some(timer);
+		}
+	}
+	
+	fn tick(& mut self) {
+		self.universe.tick();
+		
+		if let Some(gif) = &mut self.gif {
+			match gif.add_frame(&self.universe) {
+				Ok(()) if gif.byte_size() >= GIF_BUDGET => {
+					let frames = gif.frame_count();
+					self.save_gif();
+					self.show_error(&format!(
+						"Recording stopped after {} frames, as the GIF reached {} MB",
+						frames, GIF_BUDGET / (1024 * 1024)));
+				}
+				Ok(()) => {}
+				Err(error) => {
+					self.gif = None;
+					self.record_gif_button.set_text_content(Some("Record GIF"));
+					self.show_error(&error.to_string());
+				}
+			}
+		}
+
+		// Several ticks can happen between animation frames
+		if let Some(changes) = self.universe.changes() {
+			match &mut self.dirty {
+				Some(dirty) => dirty.merge(changes),
+				None => self.dirty = Some(changes.clone())
+			}
+		}
+	}
+	
+	// Pictures look like the canvas
+	fn image_style() -> ImageStyle {
+		CONSTANTS.with(|c| ImageStyle {
+			cell_size: CELL_SIZE,
+			grid_lines: true,
+			grid_color: Color::from_hex(c.grid_color).expect("The grid colour is hex"),
+			dead_color: Color::from_hex(c.dead_color).expect("The dead colour is hex"),
+			alive_color: Color::from_hex(c.alive_color).expect("The alive colour is hex"),
+		})
+	}
+	
+	fn save_png(& mut self) {
+		match write_png(&self.universe, &Ui::image_style()) {
+			Ok(png) => self.download(&png, "image/png", "game-of-life.png"),
+			Err(error) => self.show_error(&error.to_string())
+		}
+	}
+	
+	// Starts recording each generation, or stops and saves what was recorded.
+	// Frames are shown for as long as the current speed shows a generation.
+	// Recording stops by itself once the GIF reaches GIF_BUDGET.
+	fn record_gif(& mut self) {
+		match self.gif {
+			Some(_) => self.save_gif(),
+			None => {
+				let delay = (1000.0 / self.ticks_per_second_input.value_as_number()) as u32;
+				let mut gif = GifWriter::new(Ui::image_style(), delay);
+				match gif.add_frame(&self.universe) {
+					Ok(()) => {
+						self.gif = Some(gif);
+						self.record_gif_button.set_text_content(Some("Stop and save GIF"));
+					}
+					Err(error) => self.show_error(&error.to_string())
+				}
+			}
+		}
+	}
+	
+	// Stops recording and saves what was recorded.
+	fn save_gif(& mut self) {
+		if let Some(gif) = self.gif.take() {
+			self.record_gif_button.set_text_content(Some("Record GIF"));
+			match gif.finish() {
+				Ok(bytes) => self.download(&bytes, "image/gif", "game-of-life.gif"),
+				Err(error) => self.show_error(&error.to_string())
 			}
-			None => {}
 		}
 	}
 	
+	fn show_error(&self, message: &str) {
+		// Nothing more can be done if even this fails
+		let _ = self.window.alert_with_message(message);
+	}
+	
+	// Has the browser save a file, through a link to it that's clicked
+	// without ever being added to the page.
+	fn download(& mut self, bytes: &[u8], mime_type: &str, file_name: &str) {
+		if let Some(url) = self.download_url.take() {
+			Url::revoke_object_url(&url).expect("Could not revoke the last download's URL");
+		}
+		
+		let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+		let options = BlobPropertyBag::new();
+		options.set_type(mime_type);
+		let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+			.expect("Could not create a blob");
+		let url = Url::create_object_url_with_blob(&blob)
+			.expect("Could not create a URL for the blob");
+		
+		let link = self.document.create_element("a")
+			.expect("Could not create a link")
+			.dyn_into::<HtmlAnchorElement>()
+			.expect("Expected a link");
+		link.set_href(&url);
+		link.set_download(file_name);
+		link.click();
+		
+		self.download_url = Some(url);
+	}
+	
 	fn play_pause(& mut self) {
 		match self.timer {
 			Some(_) => self.pause(),
@@ -298,33 +804,183 @@ impl Ui {
 		
 		self.timer = None;
 		
-		match self.animation_id {
-			Some(a) => {
-				self.window.cancel_animation_frame(a)
-					.expect("Can't cancel animcation");
-				self.animation_id = None;
-			},
-			None => {}
+		if let Some(a) = self.animation_id {
+			self.window.cancel_animation_frame(a)
+				.expect("Can't cancel animcation");
+			self.animation_id = None;
 		}
 	}
 	
 	fn clear(& mut self) {
-    	self.universe = Universe::new(WIDTH, HEIGHT);
+		self.universe.clear();
+		self.dirty = None;
+		self.draw_cells();
+	}
+	
+	fn step_back(& mut self) {
+		if self.timer.is_some() {
+			self.pause();
+		}
+		if self.universe.step_back() {
+			self.redraw_after_history();
+		}
+	}
+	
+	fn undo(& mut self) {
+		if self.universe.undo() {
+			self.redraw_after_history();
+		}
+	}
+	
+	fn redo(& mut self) {
+		if self.universe.redo() {
+			self.redraw_after_history();
+		}
+	}
+	
+	// Undoing can jump to any earlier state, so the changes collected since
+	// the last frame no longer apply
+	fn redraw_after_history(& mut self) {
+		self.dirty = None;
 		self.draw_cells();
+		self.drawn_generation = self.universe.generation();
 	}
 	
 	fn randomize(& mut self) {
-		self.universe.randomize();
+		let seed = self.universe.randomize();
+		self.seed_input.set_value(&seed.to_string());
 		self.draw_cells();
 	}
 	
+	// Recreates the soup for a seed typed (or pasted) into the seed box, so a
+	// run someone else shared can be replayed.
+	fn randomize_from_seed_input(& mut self) {
+		if let Ok(seed) = self.seed_input.value().trim().parse::<u64>() {
+			self.universe.randomize_with(seed, 0.5, None);
+			self.draw_cells();
+		}
+	}
+	
+	fn rotate(& mut self) {
+		self.rotation = self.rotation.next();
+		self.rotate_button.set_text_content(Some(rotation_label(self.rotation)));
+		if self.paste.is_some() {
+			self.draw_cells();
+		}
+	}
+	
+	// Copies the selection into the clipboard, and returns it as RLE for the
+	// system clipboard.
+	fn copy(& mut self, cut: bool) -> Option<String> {
+		let selection = self.selection?;
+		let pattern = match cut {
+			true => self.universe.cut_region(selection),
+			false => self.universe.copy_region(selection)
+		};
+		let rle = write_rle(&pattern);
+		self.clipboard = Some(pattern);
+		if cut {
+			self.selection = None;
+			self.draw_cells();
+		}
+		Some(rle)
+	}
+	
+	fn copy_to_system_clipboard(& mut self, cut: bool) {
+		if let Some(rle) = self.copy(cut) {
+			// If this fails, the pattern is still in our own clipboard
+			let _ = self.window.navigator().clipboard().write_text(&rle);
+		}
+	}
+	
+	fn paste_from_system_clipboard(& mut self) {
+		if let Some((read, read_failed)) = &self.clipboard_read_closures {
+			let _ = self.window.navigator().clipboard().read_text().then2(read, read_failed);
+		}
+	}
+	
+	// Starts pasting a pattern read from the system clipboard, or if there
+	// isn't one there, our own clipboard's. Patterns larger than the universe
+	// are refused.
+	fn begin_paste(& mut self, text: Option<String>) {
+		let pattern = text
+			.and_then(|text| parse_pattern(&text).ok())
+			.or_else(|| self.clipboard.clone());
+		
+		if let Some(pattern) = pattern {
+			let (height, width) = match self.rotation {
+				Rotation::Clockwise90 | Rotation::Clockwise270 => (pattern.width(), pattern.height()),
+				Rotation::None | Rotation::Clockwise180 => (pattern.height(), pattern.width())
+			};
+			if height > self.universe.height() || width > self.universe.width() {
+				self.show_error(&format!(
+					"The {}x{} pattern is larger than the {}x{} universe",
+					width, height, self.universe.width(), self.universe.height()));
+				return;
+			}
+			
+			let (row, col) = self.selection.map_or((0, 0), |selection| (selection.row, selection.column));
+			self.paste = Some((pattern, row, col));
+			self.draw_cells();
+		}
+	}
+	
+	// Keyboard shortcuts and the clipboard events they raise are left alone
+	// while typing in the form
+	fn is_typing(&self) -> bool {
+		self.document.active_element()
+			.is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"))
+	}
+	
+	fn copy_event(& mut self, clipboard_event: ClipboardEvent, cut: bool) {
+		if self.is_typing() {
+			return;
+		}
+		if let (Some(rle), Some(data)) = (self.copy(cut), clipboard_event.clipboard_data()) {
+			if data.set_data("text/plain", &rle).is_ok() {
+				clipboard_event.prevent_default();
+			}
+		}
+	}
+	
+	fn paste_event(& mut self, clipboard_event: ClipboardEvent) {
+		if self.is_typing() {
+			return;
+		}
+		let text = clipboard_event.clipboard_data()
+			.and_then(|data| data.get_data("text/plain").ok());
+		clipboard_event.prevent_default();
+		self.begin_paste(text);
+	}
+	
+	fn key_down(& mut self, keyboard_event: KeyboardEvent) {
+		if self.is_typing() || keyboard_event.ctrl_key() || keyboard_event.meta_key() {
+			return;
+		}
+		match keyboard_event.key().as_str() {
+			"Escape" => {
+				self.paste = None;
+				self.selection = None;
+				self.draw_cells();
+			},
+			"r" | "R" => self.rotate(),
+			"Delete" | "Backspace" => {
+				if let Some(selection) = self.selection {
+					self.universe.clear_region(selection);
+					self.draw_cells();
+				}
+			},
+			_ => {}
+		}
+	}
+	
 	fn update_ticks_per_second(& mut self) {
 		if self.timer.is_some() {
 			self.reset_timer();
 		}
 	}
 	
-	fn canvas_click(& mut self, mouse_event: MouseEvent) {
+	fn cell_at_mouse(&self, mouse_event: &MouseEvent) -> (u32, u32) {
 		let bounding_rect = self.canvas_element.get_bounding_client_rect();
 		
 		let scale_x = (self.canvas.width() as f64) / bounding_rect.width();
@@ -333,11 +989,78 @@ impl Ui {
 		let canvas_left = (mouse_event.client_x() as f64 - bounding_rect.left()) * scale_x;
 		let canvas_top = (mouse_event.client_y() as f64 - bounding_rect.top()) * scale_y;
 
-		let row = min(canvas_top as u32 / (CELL_SIZE + 1), HEIGHT - 1);
-		let col = min(canvas_left as u32 / (CELL_SIZE + 1), WIDTH - 1);
+		let row = min(canvas_top.max(0.0) as u32 / (CELL_SIZE + 1), HEIGHT - 1);
+		let col = min(canvas_left.max(0.0) as u32 / (CELL_SIZE + 1), WIDTH - 1);
+		(row, col)
+	}
+	
+	fn canvas_mouse_down(& mut self, mouse_event: MouseEvent) {
+		if self.paste.is_none() {
+			self.drag_start = Some(self.cell_at_mouse(&mouse_event));
+			self.dragged = false;
+		}
+	}
+	
+	fn canvas_mouse_move(& mut self, mouse_event: MouseEvent) {
+		let (row, col) = self.cell_at_mouse(&mouse_event);
+		
+		if let Some((_, top, left)) = &mut self.paste {
+			if (*top, *left) != (row, col) {
+				*top = row;
+				*left = col;
+				self.draw_cells();
+			}
+		} else if let Some((start_row, start_col)) = self.drag_start {
+			// The button was let go outside the canvas
+			if mouse_event.buttons() & 1 == 0 {
+				self.drag_start = None;
+				return;
+			}
+			
+			if self.dragged || (row, col) != (start_row, start_col) {
+				self.dragged = true;
+				let selection = Rect::new(
+					min(row, start_row),
+					min(col, start_col),
+					max(col, start_col) - min(col, start_col) + 1,
+					max(row, start_row) - min(row, start_row) + 1);
+				if self.selection != Some(selection) {
+					self.selection = Some(selection);
+					self.draw_cells();
+				}
+			}
+		}
+	}
+	
+	fn canvas_click(& mut self, mouse_event: MouseEvent) {
+		let (row, col) = self.cell_at_mouse(&mouse_event);
 
-		self.universe.toggle_cell(row, col);
+		if let Some((pattern, _, _)) = self.paste.take() {
+			self.universe.stamp(&pattern, row, col, self.rotation, self.mirror_input.checked());
+		} else if self.dragged {
+			// This click ends a drag, which leaves the selection in place
+			self.dragged = false;
+			return;
+		} else {
+			self.selection = None;
+			
+			// With a pattern chosen, clicks stamp it; otherwise they toggle a cell
+			let id = self.pattern_select.value();
+			match LIBRARY.iter().find(|entry| entry.id == id) {
+				Some(entry) => self.universe.stamp(&entry.pattern(), row, col, self.rotation, self.mirror_input.checked()),
+				None => self.universe.toggle_cell(row, col)
+			}
+		}
 
 		self.draw_cells();
 	}
+}
+
+fn rotation_label(rotation: Rotation) -> &'static str {
+	match rotation {
+		Rotation::None => "Rotate 0°",
+		Rotation::Clockwise90 => "Rotate 90°",
+		Rotation::Clockwise180 => "Rotate 180°",
+		Rotation::Clockwise270 => "Rotate 270°"
+	}
 }
\ No newline at end of file
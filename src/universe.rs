@@ -1,50 +1,281 @@
-mod utils;
-mod web_sys_mixins;
-
 extern crate fixedbitset;
+use array2d::Array2D;
 use fixedbitset::FixedBitSet;
 
+use crate::bitwise;
+use crate::cells::{self, LiveCells};
+use crate::census::{self, Census, CensusObject};
+use crate::changes::ChangeSet;
+use crate::cycle::{CycleDetector, Stability};
+use crate::engine::{Engine, EngineError, Grid};
+use crate::history::{History, HistoryKind, Snapshot};
+use crate::pattern::{Pattern, Rotation};
+use crate::random::{self, Random};
+use crate::rect::Rect;
+use crate::rule::Rule;
+use crate::statistics::{GenerationStats, Statistics};
+use crate::topology::Topology;
+
+/// What happens to cells shifted past the edge of a universe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// They come back in on the opposite side.
+    Wrap,
+    /// They're lost.
+    Clip,
+}
+
+/// How `combine` merges another set of cells into a universe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BooleanOp {
+    /// Alive in either.
+    Union,
+    /// Alive in both.
+    Intersection,
+    /// Alive here but not in the other.
+    Difference,
+    /// Alive in exactly one.
+    Xor,
+}
+
+/// Why cells meant to replace a whole universe couldn't: there wasn't
+/// exactly one for each of its cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SizeMismatch {
+    /// A grid with a different number of rows or columns than the universe.
+    Grid { width: u32, height: u32, rows: usize, columns: usize },
+    /// A list in row-major order with a different number of cells.
+    RowMajor { width: u32, height: u32, len: usize },
+}
+
+/// A size with more cells than a universe can hold, which is u32::MAX.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UniverseTooLarge {
+    pub width: u32,
+    pub height: u32,
+}
+
 pub struct Universe {
     width: u32,
     height: u32,
+    rule: Rule,
+    topology: Topology,
     cells: FixedBitSet,
-    generation: i64
+    generation: i64,
+    track_changes: bool,
+    changes: Option<ChangeSet>,
+    history: Option<History>,
+    cycles: Option<CycleDetector>,
+    cycle_window: usize,
+    statistics: Option<Statistics>
 }
 
+/// How many recent generations cycle detection remembers unless told
+/// otherwise.
+pub const DEFAULT_CYCLE_WINDOW: usize = 1024;
+
 impl Universe {
     fn get_index(&self, row: u32, column: u32) -> usize {
         (row * self.width + column) as usize
     }
 
+    // The index of a cell after mapping it through the topology, or None if
+    // it lies outside a bounded universe.
+    fn wrapped_index(&self, row: i64, column: i64) -> Option<usize> {
+        self.topology
+            .wrap(row, column, self.width, self.height)
+            .map(|(row, column)| self.get_index(row, column))
+    }
+
     fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
         let mut count = 0;
-        for delta_row in [-1i32, 0, 1].iter().cloned() {
-            for delta_col in [-1i32, 0, 1].iter().cloned() {
+        for delta_row in [-1i64, 0, 1].iter().cloned() {
+            for delta_col in [-1i64, 0, 1].iter().cloned() {
                 if delta_row == 0 && delta_col == 0 {
                     continue;
                 }
 
-                let mut neighbor_row = row as i32 + delta_row;
-                if neighbor_row < 0 {
-                	neighbor_row += self.height as i32;
-                }
-                
-                let mut neighbor_col = column as i32 + delta_col;
-                if neighbor_col < 0 {
-                	neighbor_col += self.width as i32;
+                let neighbor_row = row as i64 + delta_row;
+                let neighbor_col = column as i64 + delta_col;
+
+                if let Some(idx) = self.wrapped_index(neighbor_row, neighbor_col) {
+                    count += self.cells[idx] as u8;
                 }
-                
-                let idx = self.get_index(neighbor_row as u32, neighbor_col as u32);
-                count += self.cells[idx] as u8;
             }
         }
         count
     }
+
+    fn record_changes(&mut self, next: &FixedBitSet) {
+        if self.track_changes {
+            self.changes = Some(ChangeSet::between(self.width, &self.cells, next));
+        }
+        if self.statistics.is_some() {
+            let births = next.difference_count(&self.cells) as u64;
+            let deaths = self.cells.difference_count(next) as u64;
+            let stats = self.measure(next, self.generation + 1, births, deaths);
+            if let Some(statistics) = &mut self.statistics {
+                statistics.push(stats);
+            }
+        }
+    }
+
+    fn measure(&self, cells: &FixedBitSet, generation: i64, births: u64, deaths: u64) -> GenerationStats {
+        let population = cells.count_ones(..) as u64;
+        let bounding_box = cells::bounding_box(cells, self.width, self.height);
+        let size = self.width as u64 * self.height as u64;
+        let density = if size == 0 { 0.0 } else { population as f64 / size as f64 };
+
+        GenerationStats {
+            generation,
+            population,
+            births,
+            deaths,
+            bounding_box,
+            density,
+        }
+    }
+
+    // Going back in time or editing the cells makes the statistics for the
+    // current generation and any later ones stale, so they're dropped and the
+    // current generation measured again.
+    fn remeasure_statistics(&mut self) {
+        if self.statistics.is_some() {
+            let stats = self.measure(&self.cells, self.generation, 0, 0);
+            if let Some(statistics) = &mut self.statistics {
+                statistics.truncate_from(self.generation);
+                statistics.push(stats);
+            }
+        }
+    }
+
+    fn snapshot(&self, kind: HistoryKind) -> Snapshot {
+        Snapshot {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+            generation: self.generation,
+            kind,
+        }
+    }
+
+    // Saves the current state in the history, if there is one, before it's
+    // changed.
+    fn remember(&mut self, kind: HistoryKind) {
+        if kind == HistoryKind::Edit {
+            self.reset_cycle_detection();
+        }
+        if self.history.is_some() {
+            let snapshot = self.snapshot(kind);
+            if let Some(history) = &mut self.history {
+                history.record(snapshot);
+            }
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.width = snapshot.width;
+        self.height = snapshot.height;
+        self.cells = snapshot.cells;
+        self.generation = snapshot.generation;
+        self.changes = None;
+        self.reset_cycle_detection();
+        self.remeasure_statistics();
+    }
+
+    // Moves every live cell to where `map` says, in a universe of a new size,
+    // dropping those it maps to None. Counts as an edit.
+    fn remap<F>(&mut self, width: u32, height: u32, map: F)
+    where
+        F: Fn(u32, u32) -> Option<(u32, u32)>,
+    {
+        self.remember(HistoryKind::Edit);
+
+        let mut cells = FixedBitSet::with_capacity((width * height) as usize);
+        for (row, column) in self.live_cells() {
+            if let Some((row, column)) = map(row, column) {
+                cells.insert((row * width + column) as usize);
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.changes = None;
+        self.remeasure_statistics();
+    }
+
+    // The cells of this universe covered by live cells placed at an offset.
+    fn mask<I>(&self, live_cells: I, row: i64, column: i64) -> FixedBitSet
+    where
+        I: Iterator<Item = (i64, i64)>,
+    {
+        let mut mask = FixedBitSet::with_capacity(self.cells.len());
+        for (cell_row, cell_column) in live_cells {
+            if let Some(idx) = self.wrapped_index(row + cell_row, column + cell_column) {
+                mask.insert(idx);
+            }
+        }
+        mask
+    }
+
+    fn apply_mask(&mut self, mask: &FixedBitSet, op: BooleanOp) {
+        self.remember(HistoryKind::Edit);
+        match op {
+            BooleanOp::Union => self.cells.union_with(mask),
+            BooleanOp::Intersection => self.cells.intersect_with(mask),
+            BooleanOp::Difference => self.cells.difference_with(mask),
+            BooleanOp::Xor => self.cells.symmetric_difference_with(mask),
+        }
+        self.remeasure_statistics();
+    }
+
+    // The cells' underlying words, for sharing with JavaScript without a copy.
+    #[cfg(feature = "web")]
+    pub(crate) fn cell_words(&self) -> &[usize] {
+        self.cells.as_slice()
+    }
+
+    fn reset_cycle_detection(&mut self) {
+        if let Some(cycles) = &mut self.cycles {
+            cycles.reset();
+        }
+    }
+
+    // Compares the current generation with the earlier ones. Edits reset the
+    // search, so a tick also looks at the state it started from when there's
+    // nothing to compare with.
+    // Once a result is in, nothing more is looked at until the next edit.
+    fn detect_cycles(&mut self, only_if_reset: bool) {
+        match &self.cycles {
+            Some(cycles) if !cycles.is_settled() && (!only_if_reset || cycles.is_empty()) => {}
+            _ => return,
+        }
+
+        let live_cells: Vec<(i64, i64)> = self.live_cells()
+            .map(|(row, column)| (row as i64, column as i64))
+            .collect();
+        if let Some(cycles) = &mut self.cycles {
+            cycles.observe(self.generation, &live_cells);
+        }
+    }
 }
 
 /// Public methods, exported to JavaScript.
 impl Universe {
     pub fn tick(&mut self) {
+        let next = bitwise::next_generation(&self.cells, self.width, self.height, self.rule, self.topology);
+        self.remember(HistoryKind::Tick);
+        self.detect_cycles(true);
+        self.record_changes(&next);
+        self.cells = next;
+        self.generation += 1;
+        self.detect_cycles(false);
+    }
+
+    /// Advances one generation a cell at a time. This is much slower than
+    /// `tick`, and is kept as a reference to check it against.
+    pub fn tick_scalar(&mut self) {
 
         let mut next = self.cells.clone();
 
@@ -55,55 +286,91 @@ impl Universe {
                 let cell = self.cells[idx];
                 let live_neighbors = self.live_neighbor_count(row, col);
 
-				let next_cell = match (cell, live_neighbors) {
-
-                    // Rule 1: Any live cell with fewer than two live neighbours
-                    // dies, as if caused by underpopulation.
-				    (true, x) if x < 2 => false,
-
-                    // Rule 2: Any live cell with two or three live neighbours
-                    // lives on to the next generation.
-				    (true, 2) | (true, 3) => true,
-
-                    // Rule 3: Any live cell with more than three live
-                    // neighbours dies, as if by overpopulation.
-				    (true, x) if x > 3 => false,
-
-                    // Rule 4: Any dead cell with exactly three live neighbours
-                    // becomes a live cell, as if by reproduction.
-				    (false, 3) => true,
-
-                    // All other cells remain in the same state.
-				    (otherwise, _) => otherwise
-				};
-
-            	next.set(idx, next_cell);
+            	next.set(idx, self.rule.next_state(cell, live_neighbors));
             }
         }
 
+        self.remember(HistoryKind::Tick);
+        self.detect_cycles(true);
+        self.record_changes(&next);
         self.cells = next;
         self.generation += 1;
+        self.detect_cycles(false);
     }
 
     pub fn new(width: u32, height: u32) -> Universe {
+        Universe::with_rule(width, height, Rule::conway())
+    }
+
+    pub fn with_rule(width: u32, height: u32, rule: Rule) -> Universe {
+        Universe::with_rule_and_topology(width, height, rule, Topology::Torus)
+    }
+
+    pub fn with_topology(width: u32, height: u32, topology: Topology) -> Universe {
+        Universe::with_rule_and_topology(width, height, Rule::conway(), topology)
+    }
 
-        let size = (width * height) as usize;
-	    let cells = FixedBitSet::with_capacity(size);
+    /// Panics if the universe would have more than u32::MAX cells; see
+    /// `try_with_rule_and_topology`.
+    pub fn with_rule_and_topology(width: u32, height: u32, rule: Rule, topology: Topology) -> Universe {
+        Universe::try_with_rule_and_topology(width, height, rule, topology)
+            .expect("A universe can have at most u32::MAX cells")
+    }
+
+    /// A universe, or an error if it would have more than u32::MAX cells,
+    /// for sizes that come from outside the program.
+    pub fn try_with_rule_and_topology(
+        width: u32,
+        height: u32,
+        rule: Rule,
+        topology: Topology,
+    ) -> Result<Universe, UniverseTooLarge> {
+        let size = width
+            .checked_mul(height)
+            .ok_or(UniverseTooLarge { width, height })?;
+	    let cells = FixedBitSet::with_capacity(size as usize);
 
-        Universe {
+        Ok(Universe {
             width,
             height,
+            rule,
+            topology,
             cells,
-            generation: 0
-        }
+            generation: 0,
+            track_changes: false,
+            changes: None,
+            history: None,
+            cycles: None,
+            cycle_window: DEFAULT_CYCLE_WINDOW,
+            statistics: None
+        })
     }
     
-    pub fn randomize(&mut self) {
-        let size = (self.width * self.height) as usize;
+    /// Fills the universe with a random soup at 50% density, and returns the
+    /// seed that was used so the soup can be recreated.
+    pub fn randomize(&mut self) -> u64 {
+        let seed = random::new_seed();
+        self.randomize_with(seed, 0.5, None);
+        seed
+    }
+
+    /// Fills a region (or, given None, the whole universe) with a soup where
+    /// each cell is alive with probability `density`. Cells outside the
+    /// region are untouched, and the same seed always gives the same soup.
+    pub fn randomize_with(&mut self, seed: u64, density: f64, region: Option<Rect>) {
+        let region = region.map_or(Rect::new(0, 0, self.width, self.height), |region| {
+            region.clipped(self.width, self.height)
+        });
+        let mut random = Random::new(seed);
+        self.remember(HistoryKind::Edit);
 
-    	for i in 0..size {
-        	self.cells.set(i, js_sys::Math::random() < 0.5);
-	    }
+        for row in region.row..region.bottom() {
+            for column in region.column..region.right() {
+                let idx = self.get_index(row, column);
+                self.cells.set(idx, random.next_f64() < density);
+            }
+        }
+        self.remeasure_statistics();
     }
 
     pub fn width(&self) -> u32 {
@@ -114,32 +381,581 @@ impl Universe {
         self.height
     }
 
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.reset_cycle_detection();
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// When enabled, each tick records which cells were born and which died.
+    pub fn set_change_tracking(&mut self, enabled: bool) {
+        self.track_changes = enabled;
+        if !enabled {
+            self.changes = None;
+        }
+    }
+
+    /// The cells that changed in the most recent tick, if change tracking
+    /// was enabled for it.
+    pub fn changes(&self) -> Option<&ChangeSet> {
+        self.changes.as_ref()
+    }
+
+    /// When enabled, each generation is compared with the earlier ones to
+    /// spot when the universe stops changing, starts repeating or turns into
+    /// a spaceship. Edits start the search over.
+    pub fn set_cycle_detection(&mut self, enabled: bool) {
+        if !enabled {
+            self.cycles = None;
+        } else if self.cycles.is_none() {
+            self.cycles = Some(CycleDetector::new(self.cycle_window));
+            self.detect_cycles(false);
+        }
+    }
+
+    /// How many of the most recent generations cycle detection compares
+    /// with, `DEFAULT_CYCLE_WINDOW` unless changed. Longer periods aren't
+    /// found, but memory use stays bounded however long the universe runs.
+    pub fn set_cycle_detection_window(&mut self, generations: usize) {
+        self.cycle_window = generations.max(1);
+        if let Some(cycles) = &mut self.cycles {
+            cycles.set_window(self.cycle_window);
+        }
+    }
+
+    /// What cycle detection has found so far, if it's enabled.
+    pub fn stability(&self) -> Option<Stability> {
+        self.cycles.as_ref().map(CycleDetector::stability)
+    }
+
+    /// Records statistics for each generation from now on, keeping the most
+    /// recent `capacity` of them. A capacity of 0 stops recording and forgets
+    /// them.
+    pub fn set_statistics_capacity(&mut self, capacity: usize) {
+        if capacity == 0 {
+            self.statistics = None;
+            return;
+        }
+
+        let mut statistics = Statistics::new(capacity);
+        match &self.statistics {
+            Some(previous) => previous.iter().for_each(|stats| statistics.push(*stats)),
+            None => statistics.push(self.measure(&self.cells, self.generation, 0, 0)),
+        }
+        self.statistics = Some(statistics);
+    }
+
+    pub fn statistics(&self) -> Option<&Statistics> {
+        self.statistics.as_ref()
+    }
+
+    /// How many cells are alive.
+    pub fn population(&self) -> u64 {
+        self.cells.count_ones(..) as u64
+    }
+
+    /// The (row, column) of every live cell, row by row.
+    pub fn live_cells(&self) -> LiveCells<'_> {
+        LiveCells::new(&self.cells, self.width, self.height, Rect::new(0, 0, self.width, self.height))
+    }
+
+    /// The live cells inside a region, clipped to the universe, with their
+    /// coordinates in the universe rather than the region.
+    pub fn cells_in_rect(&self, region: Rect) -> LiveCells<'_> {
+        LiveCells::new(&self.cells, self.width, self.height, region)
+    }
+
+    /// The smallest rectangle holding every live cell, or None if there are
+    /// none.
+    pub fn bounding_box(&self) -> Option<Rect> {
+        cells::bounding_box(&self.cells, self.width, self.height)
+    }
+
+    /// Coordinates past the edge are mapped through the topology; in a bounded
+    /// universe they are always dead.
     pub fn cell_at(&self, row: u32, column: u32) -> bool {
-        self.cells[self.get_index(row, column)]
+        self.wrapped_index(row as i64, column as i64)
+            .is_some_and(|idx| self.cells[idx])
     }
     
+    pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
+        if let Some(idx) = self.wrapped_index(row as i64, column as i64) {
+            self.remember(HistoryKind::Edit);
+            self.cells.set(idx, alive);
+            self.remeasure_statistics();
+        }
+    }
+
     pub fn toggle_cell(&mut self, row: u32, column: u32) {
-        let idx = self.get_index(row, column);
-        self.cells.set(idx, !self.cells[idx]);
+        if let Some(idx) = self.wrapped_index(row as i64, column as i64) {
+            self.remember(HistoryKind::Edit);
+            self.cells.toggle(idx);
+            self.remeasure_statistics();
+        }
+    }
+
+    /// Replaces every cell with those of a grid with `height` rows and
+    /// `width` columns, where any value other than the default (0 or false)
+    /// is alive. A grid of any other size is refused, leaving the universe as
+    /// it was; `load_pattern` places a smaller one.
+    pub fn set_cells<T>(&mut self, cells: Array2D<T>) -> Result<(), SizeMismatch>
+    where
+        T: Copy + Default + PartialEq,
+    {
+        if (cells.num_rows(), cells.num_columns()) != (self.height as usize, self.width as usize) {
+            return Err(SizeMismatch::Grid {
+                width: self.width,
+                height: self.height,
+                rows: cells.num_rows(),
+                columns: cells.num_columns(),
+            });
+        }
+
+        self.remember(HistoryKind::Edit);
+        self.cells.clear();
+        for (idx, cell) in cells.elements_row_major_iter().enumerate() {
+            self.cells.set(idx, *cell != T::default());
+        }
+        self.remeasure_statistics();
+        Ok(())
+    }
+
+    /// Replaces every cell, given one per cell in row-major order. Any other
+    /// number of cells is refused, leaving the universe as it was.
+    pub fn set_cells_row_major(&mut self, cells: &[bool]) -> Result<(), SizeMismatch> {
+        if cells.len() != self.cells.len() {
+            return Err(SizeMismatch::RowMajor {
+                width: self.width,
+                height: self.height,
+                len: cells.len(),
+            });
+        }
+
+        self.remember(HistoryKind::Edit);
+        self.cells.clear();
+        for (idx, alive) in cells.iter().enumerate() {
+            self.cells.set(idx, *alive);
+        }
+        self.remeasure_statistics();
+        Ok(())
+    }
+
+    /// Makes the listed cells alive and every other cell dead. Coordinates
+    /// past the edge are mapped through the topology, as with `set_cell`.
+    pub fn set_live_cells<I>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = (u32, u32)>,
+    {
+        self.remember(HistoryKind::Edit);
+        self.cells.clear();
+        for (row, column) in cells {
+            if let Some(idx) = self.wrapped_index(row as i64, column as i64) {
+                self.cells.insert(idx);
+            }
+        }
+        self.remeasure_statistics();
+    }
+
+    /// Every cell, as a grid with `height` rows and `width` columns.
+    pub fn to_array2d(&self) -> Array2D<bool> {
+        let mut cells = Array2D::filled_with(false, self.height as usize, self.width as usize);
+        for (row, column) in self.live_cells() {
+            cells[(row as usize, column as usize)] = true;
+        }
+        cells
+    }
+
+    /// Kills every cell and starts counting generations from 0 again.
+    pub fn clear(&mut self) {
+        self.remember(HistoryKind::Edit);
+        self.cells.clear();
+        self.generation = 0;
+        self.changes = None;
+        self.remeasure_statistics();
+    }
+
+    /// Keeps earlier states so they can be undone, using at most `bytes` of
+    /// memory; the oldest states are forgotten first. Ticks, edits such as
+    /// `set_cell`, `randomize` and `clear`, and loaded patterns are recorded.
+    /// A budget of 0 turns the history off and forgets it.
+    pub fn set_history_budget(&mut self, bytes: usize) {
+        match (&mut self.history, bytes) {
+            (_, 0) => self.history = None,
+            (Some(history), _) => history.set_budget(bytes),
+            (None, _) => self.history = Some(History::new(bytes)),
+        }
+    }
+
+    /// The memory the history is currently holding, in bytes.
+    pub fn history_memory(&self) -> usize {
+        self.history.as_ref().map_or(0, History::memory_used)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.as_ref().is_some_and(History::can_undo)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.as_ref().is_some_and(History::can_redo)
+    }
+
+    /// Goes back to the state before the last tick or edit. Returns false if
+    /// there's nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let current = self.snapshot(HistoryKind::Edit);
+        match self.history.as_mut().and_then(|history| history.undo(current)) {
+            Some(previous) => {
+                self.restore(previous);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapplies the last undone tick or edit. Returns false if there's
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let current = self.snapshot(HistoryKind::Edit);
+        match self.history.as_mut().and_then(|history| history.redo(current)) {
+            Some(next) => {
+                self.restore(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// What `undo` would undo.
+    pub fn last_change(&self) -> Option<HistoryKind> {
+        self.history.as_ref().and_then(History::last_kind)
+    }
+
+    /// Goes back one generation, but only if the last thing to happen was a
+    /// tick rather than an edit.
+    pub fn step_back(&mut self) -> bool {
+        self.last_change() == Some(HistoryKind::Tick) && self.undo()
     }
     
     pub fn generation(&self) -> i64 {
     	self.generation
     }
+
+    /// Places a pattern's live cells with its top-left corner at (row, column),
+    /// mapping anything past the edge through the topology. The universe's rule
+    /// is left unchanged.
+    pub fn load_pattern(&mut self, pattern: &Pattern, row: u32, column: u32) {
+        self.remember(HistoryKind::Edit);
+        for (pattern_row, pattern_column) in pattern.live_cells() {
+            let idx = self.wrapped_index(
+                row as i64 + pattern_row as i64,
+                column as i64 + pattern_column as i64);
+
+            if let Some(idx) = idx {
+                self.cells.insert(idx);
+            }
+        }
+        self.remeasure_statistics();
+    }
+
+    /// Copies a pattern, mirrored and rotated as asked, with its top-left
+    /// corner at (row, column). Unlike `load_pattern`, the pattern's dead
+    /// cells are copied too, clearing whatever was underneath it.
+    pub fn stamp(&mut self, pattern: &Pattern, row: u32, column: u32, rotation: Rotation, reflect: bool) {
+        let pattern = pattern.transformed(rotation, reflect);
+        self.remember(HistoryKind::Edit);
+        for pattern_row in 0..pattern.height() {
+            for pattern_column in 0..pattern.width() {
+                let idx = self.wrapped_index(
+                    row as i64 + pattern_row as i64,
+                    column as i64 + pattern_column as i64);
+
+                if let Some(idx) = idx {
+                    self.cells.set(idx, pattern.cell_at(pattern_row, pattern_column));
+                }
+            }
+        }
+        self.remeasure_statistics();
+    }
+
+    /// The cells in a region, clipped to the universe, as a pattern of the
+    /// region's size that keeps its dead cells.
+    pub fn copy_region(&self, region: Rect) -> Pattern {
+        let region = region.clipped(self.width, self.height);
+
+        let mut pattern = Pattern::new(region.width, region.height);
+        pattern.rule = Some(self.rule);
+        for (row, column) in self.cells_in_rect(region) {
+            pattern.set_cell(row - region.row, column - region.column, true);
+        }
+        pattern
+    }
+
+    /// Kills every cell in a region.
+    pub fn clear_region(&mut self, region: Rect) {
+        self.remember(HistoryKind::Edit);
+
+        let region = region.clipped(self.width, self.height);
+        for row in region.row..region.bottom() {
+            for column in region.column..region.right() {
+                let idx = self.get_index(row, column);
+                self.cells.set(idx, false);
+            }
+        }
+        self.remeasure_statistics();
+    }
+
+    /// Copies a region and then clears it.
+    pub fn cut_region(&mut self, region: Rect) -> Pattern {
+        let pattern = self.copy_region(region);
+        self.clear_region(region);
+        pattern
+    }
+
+    /// Merges another universe's cells into this one, with its top-left
+    /// corner at (row, column), which may be negative. Its cells are mapped
+    /// through this universe's topology; everywhere else counts as dead, so
+    /// an intersection clears whatever the other universe doesn't cover.
+    pub fn combine(&mut self, other: &Universe, row: i64, column: i64, op: BooleanOp) {
+        let mask = if (other.width, other.height, row, column) == (self.width, self.height, 0, 0) {
+            other.cells.clone()
+        } else {
+            let live_cells = other
+                .live_cells()
+                .map(|(other_row, other_column)| (other_row as i64, other_column as i64));
+            self.mask(live_cells, row, column)
+        };
+        self.apply_mask(&mask, op);
+    }
+
+    /// Like `combine`, with a pattern's live cells.
+    pub fn combine_pattern(&mut self, pattern: &Pattern, row: i64, column: i64, op: BooleanOp) {
+        let live_cells = pattern
+            .live_cells()
+            .map(|(pattern_row, pattern_column)| (pattern_row as i64, pattern_column as i64));
+        let mask = self.mask(live_cells, row, column);
+        self.apply_mask(&mask, op);
+    }
+
+    /// Splits the live cells into objects and identifies each one. Cells
+    /// within two rows and columns of each other, close enough to affect a
+    /// common neighbour, belong to the same object. An oscillator whose
+    /// phases come apart, like the pentadecathlon, is only recognised in the
+    /// phases where it holds together. Objects are only identified under
+    /// B3/S23, where the built-in patterns behave as they're named; under
+    /// any other rule each one is unknown.
+    pub fn census(&self) -> Census {
+        let mut visited = FixedBitSet::with_capacity(self.cells.len());
+        let mut objects = Vec::new();
+
+        for start in self.cells.ones() {
+            if visited.put(start) {
+                continue;
+            }
+
+            // Follow neighbours through the topology, keeping unwrapped
+            // coordinates so objects that straddle an edge hold together.
+            // Each cell also keeps which way its rows and columns run
+            // compared to the first cell's, which a twisted edge turns around.
+            let mut cells = Vec::new();
+            let (start_row, start_column) = ((start as u32 / self.width) as i64, (start as u32 % self.width) as i64);
+            let mut queue = vec![(start_row, start_column, (1, 1), (start_row, start_column))];
+            while let Some((actual_row, actual_column, (row_direction, column_direction), (row, column))) = queue.pop() {
+                cells.push((row, column));
+
+                for delta_row in -2..=2 {
+                    for delta_column in -2..=2 {
+                        let neighbor = self.topology.wrap_mirrored(
+                            actual_row + delta_row,
+                            actual_column + delta_column,
+                            self.width,
+                            self.height,
+                        );
+                        if let Some(((neighbor_row, neighbor_column), (row_mirrored, column_mirrored))) = neighbor {
+                            let idx = self.get_index(neighbor_row, neighbor_column);
+                            if self.cells[idx] && !visited.put(idx) {
+                                let unwrapped = (row + row_direction * delta_row, column + column_direction * delta_column);
+                                let directions = (
+                                    if row_mirrored { -row_direction } else { row_direction },
+                                    if column_mirrored { -column_direction } else { column_direction },
+                                );
+                                queue.push((neighbor_row as i64, neighbor_column as i64, directions, unwrapped));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+            let left = cells.iter().map(|(_, column)| *column).min().unwrap_or(0);
+            let bottom = cells.iter().map(|(row, _)| *row).max().unwrap_or(0);
+            let right = cells.iter().map(|(_, column)| *column).max().unwrap_or(0);
+
+            objects.push(CensusObject {
+                name: census::identify(&cells, self.rule),
+                bounding_box: Rect::new(
+                    top.rem_euclid(self.height as i64) as u32,
+                    left.rem_euclid(self.width as i64) as u32,
+                    (right - left + 1) as u32,
+                    (bottom - top + 1) as u32),
+                population: cells.len(),
+            });
+        }
+
+        Census::new(objects)
+    }
+
+    /// Moves everything down by `rows` and right by `columns`; negative
+    /// amounts move up and left. Cells pushed past the edge either wrap
+    /// around to the other side or are lost.
+    pub fn shift(&mut self, rows: i64, columns: i64, overflow: Overflow) {
+        let (width, height) = (self.width as i64, self.height as i64);
+        self.remap(self.width, self.height, |row, column| {
+            let (row, column) = (row as i64 + rows, column as i64 + columns);
+            match overflow {
+                Overflow::Wrap => Some((row.rem_euclid(height) as u32, column.rem_euclid(width) as u32)),
+                Overflow::Clip if (0..height).contains(&row) && (0..width).contains(&column) => {
+                    Some((row as u32, column as u32))
+                }
+                Overflow::Clip => None,
+            }
+        });
+    }
+
+    /// Turns the universe clockwise. Quarter turns swap its width and height.
+    pub fn rotate(&mut self, rotation: Rotation) {
+        let (width, height) = (self.width, self.height);
+        match rotation {
+            Rotation::None => {}
+            Rotation::Clockwise90 => self.remap(height, width, |row, column| Some((column, height - 1 - row))),
+            Rotation::Clockwise180 => {
+                self.remap(width, height, |row, column| Some((height - 1 - row, width - 1 - column)))
+            }
+            Rotation::Clockwise270 => self.remap(height, width, |row, column| Some((width - 1 - column, row))),
+        }
+    }
+
+    /// Mirrors the universe left-to-right.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.width;
+        self.remap(self.width, self.height, |row, column| Some((row, width - 1 - column)));
+    }
+
+    /// Mirrors the universe top-to-bottom.
+    pub fn flip_vertical(&mut self) {
+        let height = self.height;
+        self.remap(self.width, self.height, |row, column| Some((height - 1 - row, column)));
+    }
+
+    /// Shrinks the universe to the smallest rectangle holding every live
+    /// cell, and returns where that rectangle was. An empty universe is left
+    /// as it is.
+    pub fn crop_to_live(&mut self) -> Option<Rect> {
+        let bounds = self.bounding_box()?;
+        let (top, left) = (bounds.row, bounds.column);
+        self.remap(bounds.width, bounds.height, |row, column| Some((row - top, column - left)));
+        Some(bounds)
+    }
+
+    /// Changes the universe's size, keeping the contents where they are
+    /// relative to the top-left corner. Shrinking loses cells past the new
+    /// edges.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.remap(width, height, |row, column| {
+            if row < height && column < width {
+                Some((row, column))
+            } else {
+                None
+            }
+        });
+    }
+
+    /// The smallest region containing every live cell, as a pattern that
+    /// carries the universe's rule.
+    pub fn to_pattern(&self) -> Pattern {
+        let bounds = self.bounding_box().unwrap_or_default();
+
+        let mut pattern = Pattern::new(0, 0);
+        pattern.rule = Some(self.rule);
+        for (row, column) in self.live_cells() {
+            pattern.set_cell(row - bounds.row, column - bounds.column, true);
+        }
+        pattern
+    }
 }
 
+impl Engine for Universe {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn generation(&self) -> i64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        Universe::population(self)
+    }
+
+    fn tick(&mut self) {
+        Universe::tick(self);
+    }
+
+    fn to_pattern(&self) -> Result<Pattern, EngineError> {
+        Ok(Universe::to_pattern(self))
+    }
+}
+
+impl Grid for Universe {
+    fn size(&self) -> Option<(u32, u32)> {
+        Some((self.width, self.height))
+    }
+
+    fn cell_at(&self, row: i64, column: i64) -> bool {
+        self.wrapped_index(row, column).is_some_and(|idx| self.cells[idx])
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, alive: bool) {
+        if let Some(idx) = self.wrapped_index(row, column) {
+            self.remember(HistoryKind::Edit);
+            self.cells.set(idx, alive);
+            self.remeasure_statistics();
+        }
+    }
+
+    // Only the part of the viewport over the universe itself is looked at
+    fn live_cells_in(&self, top: i64, left: i64, height: u32, width: u32) -> Vec<(i64, i64)> {
+        let clip = |start: i64, length: u32, limit: u32| {
+            let end = start.saturating_add(length as i64).clamp(0, limit as i64);
+            let start = start.clamp(0, limit as i64);
+            (start as u32, (end - start) as u32)
+        };
+        let (row, height) = clip(top, height, self.height);
+        let (column, width) = clip(left, width, self.width);
+
+        self.cells_in_rect(Rect::new(row, column, width, height))
+            .map(|(row, column)| (row as i64, column as i64))
+            .collect()
+    }
+
+    fn clear(&mut self) {
+        Universe::clear(self);
+    }
+}
+
+use std::error::Error;
 use std::fmt;
 
 impl fmt::Display for Universe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let idx = self.get_index(row, col);
-                let cell = self.cells[idx];
-                let symbol = if cell { '◼' } else { '◻' };
-                write!(f, "{}", symbol)?;
-			}
-            write!(f, "\n")?;
+        for line in self.text_rows(0, 0, self.height, self.width) {
+            writeln!(f, "{}", line)?;
 		}
 		
         Ok(())
@@ -150,6 +966,8 @@ impl PartialEq for Universe {
     fn eq(&self, other: &Self) -> bool {
         self.height == other.height &&
         self.width == other.width &&
+        self.rule == other.rule &&
+        self.topology == other.topology &&
         self.cells == other.cells
     }
 }
@@ -157,10 +975,194 @@ impl Eq for Universe {}
 
 impl fmt::Debug for Universe {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Point")
+        f.debug_struct("Universe")
          .field("width", &self.width)
          .field("height", &self.height)
+         .field("rule", &self.rule)
+         .field("topology", &self.topology)
          .field("cells", &self.to_string())
          .finish()
     }
-}
\ No newline at end of file
+}
+
+impl fmt::Display for SizeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SizeMismatch::Grid { width, height, rows, columns } => write!(
+                f,
+                "expected {} rows and {} columns of cells for a {}x{} universe, not {} rows and {} columns",
+                height, width, width, height, rows, columns
+            ),
+            SizeMismatch::RowMajor { width, height, len } => write!(
+                f,
+                "expected {} cells for a {}x{} universe, not {}",
+                *width as u64 * *height as u64,
+                width,
+                height,
+                len
+            ),
+        }
+    }
+}
+
+impl Error for SizeMismatch {}
+
+impl fmt::Display for UniverseTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} is more than the {} cells a universe can have",
+            self.width,
+            self.height,
+            u32::MAX
+        )
+    }
+}
+
+impl Error for UniverseTooLarge {}
+
+// A universe is written as its size, rule, topology and generation, and its
+// cells packed 8 to a byte in row-major order, with the first cell in the
+// lowest bit. Text formats like JSON get the bytes as base64. Change tracking,
+// history and the other optional records aren't included.
+#[cfg(feature = "serde")]
+mod serialization {
+    use std::borrow::Cow;
+
+    use fixedbitset::FixedBitSet;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Universe, UniverseTooLarge};
+    use crate::rule::Rule;
+    use crate::topology::Topology;
+
+    const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "Universe")]
+    struct UniverseData<'a> {
+        width: u32,
+        height: u32,
+        rule: Rule,
+        topology: Topology,
+        generation: i64,
+        #[serde(borrow)]
+        cells: PackedCells<'a>,
+    }
+
+    struct PackedCells<'a>(Cow<'a, [u8]>);
+
+    impl Serialize for Universe {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let size = (self.width * self.height) as usize;
+            let mut bytes = vec![0u8; size.div_ceil(8)];
+            for idx in self.cells.ones() {
+                bytes[idx / 8] |= 1 << (idx % 8);
+            }
+
+            UniverseData {
+                width: self.width,
+                height: self.height,
+                rule: self.rule,
+                topology: self.topology,
+                generation: self.generation,
+                cells: PackedCells(Cow::Owned(bytes)),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Universe {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+            let data = UniverseData::deserialize(deserializer)?;
+            // Checked the way the constructor checks it, before anything is
+            // allocated
+            let size = data
+                .width
+                .checked_mul(data.height)
+                .ok_or_else(|| D::Error::custom(UniverseTooLarge { width: data.width, height: data.height }))?
+                as usize;
+            let bytes = data.cells.0;
+            if bytes.len() != size.div_ceil(8) {
+                return Err(D::Error::custom(format!(
+                    "expected {} bytes of cells for a {}x{} universe, found {}",
+                    size.div_ceil(8),
+                    data.width,
+                    data.height,
+                    bytes.len()
+                )));
+            }
+
+            let mut cells = FixedBitSet::with_capacity(size);
+            for idx in 0..size {
+                if bytes[idx / 8] & (1 << (idx % 8)) != 0 {
+                    cells.insert(idx);
+                }
+            }
+
+            let mut universe = Universe::with_rule_and_topology(data.width, data.height, data.rule, data.topology);
+            universe.cells = cells;
+            universe.generation = data.generation;
+            Ok(universe)
+        }
+    }
+
+    impl Serialize for PackedCells<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&encode_base64(&self.0))
+            } else {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+    }
+
+    impl<'de: 'a, 'a> Deserialize<'de> for PackedCells<'a> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PackedCells<'a>, D::Error> {
+            if deserializer.is_human_readable() {
+                let text = <Cow<'de, str>>::deserialize(deserializer)?;
+                decode_base64(&text)
+                    .map(|bytes| PackedCells(Cow::Owned(bytes)))
+                    .ok_or_else(|| D::Error::custom("cells aren't valid base64"))
+            } else {
+                let bytes = <Cow<'de, [u8]>>::deserialize(deserializer)?;
+                Ok(PackedCells(bytes))
+            }
+        }
+    }
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| group | (*byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    text.push(BASE64[(group >> (18 - 6 * i) & 63) as usize] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+        text
+    }
+
+    fn decode_base64(text: &str) -> Option<Vec<u8>> {
+        let text = text.trim_end_matches('=').as_bytes();
+        let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+        for chunk in text.chunks(4) {
+            if chunk.len() == 1 {
+                return None;
+            }
+            let mut group = 0u32;
+            for (i, c) in chunk.iter().enumerate() {
+                let value = BASE64.iter().position(|b| b == c)? as u32;
+                group |= value << (18 - 6 * i);
+            }
+            for i in 0..chunk.len() - 1 {
+                bytes.push((group >> (16 - 8 * i)) as u8);
+            }
+        }
+        Some(bytes)
+    }
+}
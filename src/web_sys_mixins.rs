@@ -4,6 +4,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 extern crate web_sys;
+use web_sys::Document;
 use web_sys::Element;
 use web_sys::Event;
 use web_sys::EventTarget;
@@ -27,9 +28,9 @@ pub struct HtmlEvents<'a> {
 
 impl HtmlEvents<'_> {
 	#[allow(dead_code)]
-	pub fn add_event_listener<'a>(&self, type_: &'a str, listener: Box<dyn Fn(Event) -> ()>) -> Result<RegisteredHtmlEvent<'a>, JsValue> {
+	pub fn add_event_listener<'a>(&self, type_: &'a str, listener: Box<dyn Fn(Event) >) -> Result<RegisteredHtmlEvent<'a>, JsValue> {
 	
-   		let closure = Closure::wrap(Box::new(move |event| listener(event)) as Box<dyn FnMut(Event)>);
+   		let closure = Closure::wrap(Box::new(listener) as Box<dyn FnMut(Event)>);
    	
     	match self.event_target.add_event_listener_with_callback(type_, closure.as_ref().unchecked_ref()) {
 			Err(e) => Err(e),
@@ -37,7 +38,7 @@ impl HtmlEvents<'_> {
 				Ok(RegisteredHtmlEvent {
 					event_target: self.event_target.clone(),
 					type_,
-					closure: closure
+					closure
 				})
 		    }
 		}
@@ -53,11 +54,11 @@ impl<'a> Drop for RegisteredHtmlEvent<'a> {
 // Allow easy access to the HtmlEvents API
 
 pub trait HtmlExt {
-	fn events(&self) -> HtmlEvents;
+	fn events(&self) -> HtmlEvents<'_>;
 }
 
 impl HtmlExt for HtmlElement {
-	fn events(&self) -> HtmlEvents {
+	fn events(&self) -> HtmlEvents<'_> {
 		HtmlEvents {
 			event_target: self.dyn_ref::<EventTarget>().unwrap(),
 		}
@@ -65,7 +66,15 @@ impl HtmlExt for HtmlElement {
 }
 
 impl HtmlExt for HtmlCanvasElement {
-	fn events(&self) -> HtmlEvents {
+	fn events(&self) -> HtmlEvents<'_> {
+		HtmlEvents {
+			event_target: self.dyn_ref::<EventTarget>().unwrap(),
+		}
+	}
+}
+
+impl HtmlExt for Document {
+	fn events(&self) -> HtmlEvents<'_> {
 		HtmlEvents {
 			event_target: self.dyn_ref::<EventTarget>().unwrap(),
 		}
@@ -73,7 +82,7 @@ impl HtmlExt for HtmlCanvasElement {
 }
 
 impl HtmlExt for Element {
-	fn events(&self) -> HtmlEvents {
+	fn events(&self) -> HtmlEvents<'_> {
 		HtmlEvents {
 			event_target: self.dyn_ref::<EventTarget>().unwrap(),
 		}
@@ -91,22 +100,24 @@ pub struct IntervalSubscription {
 }
 
 // Simplified use of animation frame callbacks
+type AnimationFrameClosure = Rc<RefCell<Option<Closure<dyn std::ops::FnMut(i32)>>>>;
+
 pub struct AnimationFrameRequester {
 	#[allow(dead_code)]
 	window: Window,
 
 	#[allow(dead_code)]
-	render_loop_closure: Rc<RefCell<Option<Closure<dyn std::ops::FnMut(i32)>>>>,
+	render_loop_closure: AnimationFrameClosure,
 }
 
 pub trait WindowExt {
-	fn set_interval(&self, handler: Box<dyn Fn() -> ()>, delay: i32) -> Result<IntervalSubscription, JsValue>;
-	fn prepare_animation_frame_callback(&self, handler: Box<dyn Fn(i32) -> ()>) -> AnimationFrameRequester;
+	fn set_interval(&self, handler: Box<dyn Fn() >, delay: i32) -> Result<IntervalSubscription, JsValue>;
+	fn prepare_animation_frame_callback(&self, handler: Box<dyn Fn(i32) >) -> AnimationFrameRequester;
 }
 
 impl WindowExt for Window {
-	fn set_interval(&self, handler: Box<dyn Fn() -> ()>, delay: i32) -> Result<IntervalSubscription, JsValue> {
-   		let closure = Closure::wrap(Box::new(move || handler()) as Box<dyn FnMut()>);
+	fn set_interval(&self, handler: Box<dyn Fn() >, delay: i32) -> Result<IntervalSubscription, JsValue> {
+   		let closure = Closure::wrap(Box::new(handler) as Box<dyn FnMut()>);
 		match self.set_interval_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), delay) {
 			Err(e) => Err(e),
 			Ok(timer) => Ok(IntervalSubscription {
@@ -117,12 +128,12 @@ impl WindowExt for Window {
 		}
 	}
 
-	fn prepare_animation_frame_callback(&self, handler: Box<dyn Fn(i32) -> ()>) -> AnimationFrameRequester {
+	fn prepare_animation_frame_callback(&self, handler: Box<dyn Fn(i32) >) -> AnimationFrameRequester {
     	// TODO: Hack for requestAnimationFrame loop
 		// https://github.com/bzar/wasm-pong-rs/blob/master/src/lib.rs
 	    let render_loop_closure = Rc::new(RefCell::new(None));
     	let g = render_loop_closure.clone();
-    	*g.borrow_mut() = Some(Closure::wrap(Box::new(move |animation_id| handler(animation_id)) as Box<dyn FnMut(i32)>));    	
+    	*g.borrow_mut() = Some(Closure::wrap(Box::new(handler) as Box<dyn FnMut(i32)>));    	
 
 		AnimationFrameRequester {
 			window: self.clone(),
@@ -131,7 +142,7 @@ impl WindowExt for Window {
 	}
 }
 
-impl<'a> Drop for IntervalSubscription {
+impl Drop for IntervalSubscription {
 	fn drop(&mut self) {
 		self.window.clear_interval_with_handle(self.timer);
 	}
@@ -0,0 +1,28 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{Rule, Topology};
+
+mod common;
+use common::soup;
+
+#[test]
+pub fn test_matches_scalar_tick() {
+    let topologies = [Topology::Torus, Topology::Bounded, Topology::KleinBottle, Topology::CrossSurface];
+    let rules = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B0/S8"];
+    let sizes = [(1, 1), (3, 7), (63, 5), (64, 4), (65, 9), (96, 64), (130, 3)];
+
+    for topology in topologies.iter().cloned() {
+        for rule in rules.iter() {
+            for (width, height) in sizes.iter().cloned() {
+                let rule = Rule::parse(rule).unwrap();
+                let mut bitwise = soup(width, height, rule, topology, width as u64 * 31 + height as u64, 0.25);
+                let mut scalar = soup(width, height, rule, topology, width as u64 * 31 + height as u64, 0.25);
+
+                for _ in 0..4 {
+                    bitwise.tick();
+                    scalar.tick_scalar();
+                    assert_eq!(bitwise, scalar, "{} on a {}×{} {:?}", rule, width, height, topology);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Rect, Topology, Universe};
+
+// Every live cell found by checking each one in turn
+fn scan(universe: &Universe, region: Rect) -> Vec<(u32, u32)> {
+    let mut cells = Vec::new();
+    for row in region.row..region.bottom().min(universe.height()) {
+        for column in region.column..region.right().min(universe.width()) {
+            if universe.cell_at(row, column) {
+                cells.push((row, column));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+pub fn test_live_cells() {
+    let mut universe = Universe::new(8, 6);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 1, 2);
+
+    assert_eq!(universe.live_cells().collect::<Vec<_>>(), vec![(1, 3), (2, 4), (3, 2), (3, 3), (3, 4)]);
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(1, 2, 3, 3)));
+}
+
+#[test]
+pub fn test_empty() {
+    let universe = Universe::new(8, 6);
+    assert_eq!(universe.live_cells().count(), 0);
+    assert_eq!(universe.population(), 0);
+    assert_eq!(universe.bounding_box(), None);
+    assert_eq!(universe.cells_in_rect(Rect::new(0, 0, 8, 6)).count(), 0);
+
+    let universe = Universe::new(0, 0);
+    assert_eq!(universe.live_cells().count(), 0);
+    assert_eq!(universe.bounding_box(), None);
+}
+
+// Sizes that don't line up with words, so rows start partway through them
+#[test]
+pub fn test_matches_scan() {
+    for (width, height) in [(1, 1), (7, 5), (64, 3), (65, 9), (130, 17)] {
+        let mut universe = Universe::new(width, height);
+        universe.randomize_with(width as u64, 0.3, None);
+
+        let everything = Rect::new(0, 0, width, height);
+        assert_eq!(universe.live_cells().collect::<Vec<_>>(), scan(&universe, everything));
+        assert_eq!(universe.population() as usize, scan(&universe, everything).len());
+
+        let regions = [
+            Rect::new(0, 0, width, height),
+            Rect::new(height / 3, 0, width, height / 2),
+            Rect::new(1, width / 4, width / 2 + 1, height),
+            Rect::new(height - 1, width - 1, 10, 10),
+            Rect::new(height, 0, width, 3),
+            Rect::new(0, 2, 0, height),
+        ];
+        for region in regions.iter() {
+            assert_eq!(
+                universe.cells_in_rect(*region).collect::<Vec<_>>(),
+                scan(&universe, *region),
+                "{}x{} universe, {:?}",
+                width,
+                height,
+                region
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_bounding_box() {
+    let mut universe = Universe::with_topology(100, 50, Topology::Bounded);
+    universe.set_cell(10, 70, true);
+    universe.set_cell(20, 5, true);
+    universe.set_cell(30, 99, true);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(10, 5, 95, 21)));
+
+    universe.set_cell(49, 0, true);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(10, 0, 100, 40)));
+}
@@ -0,0 +1,107 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Grid, Rect, Rotation, Rule, Topology, Universe};
+
+fn place(universe: &mut Universe, id: &str, row: u32, column: u32, rotation: Rotation, reflect: bool) {
+    universe.stamp(&library_pattern(id).unwrap(), row, column, rotation, reflect);
+}
+
+#[test]
+pub fn test_identifies_objects_in_any_orientation() {
+    let mut universe = Universe::new(80, 80);
+    place(&mut universe, "block", 2, 2, Rotation::None, false);
+    place(&mut universe, "beehive", 2, 20, Rotation::Clockwise90, false);
+    place(&mut universe, "boat", 2, 40, Rotation::Clockwise180, true);
+    place(&mut universe, "blinker", 20, 2, Rotation::Clockwise90, false);
+    place(&mut universe, "beacon", 20, 20, Rotation::None, false);
+    place(&mut universe, "glider", 20, 40, Rotation::Clockwise270, true);
+    place(&mut universe, "lwss", 40, 2, Rotation::Clockwise90, false);
+    place(&mut universe, "pulsar", 40, 20, Rotation::None, false);
+    place(&mut universe, "long-boat", 40, 50, Rotation::None, true);
+
+    // A single cell isn't a known object
+    universe.set_cell(70, 70, true);
+
+    let census = universe.census();
+    assert_eq!(census.len(), 10);
+    let counts = census.counts();
+    for id in ["block", "beehive", "boat", "blinker", "beacon", "glider", "lwss", "pulsar", "long-boat", "unknown"].iter() {
+        assert_eq!(counts.get(id), Some(&1), "{}", id);
+    }
+
+    assert_eq!(census.objects()[0].name, Some("block"));
+    assert_eq!(census.objects()[0].bounding_box, Rect::new(2, 2, 2, 2));
+    assert_eq!(census.objects()[0].population, 4);
+}
+
+#[test]
+pub fn test_identifies_every_phase() {
+    for id in ["beacon", "pulsar", "glider", "hwss"].iter() {
+        let mut universe = Universe::new(40, 40);
+        place(&mut universe, id, 10, 10, Rotation::None, false);
+        for generation in 0..15 {
+            assert_eq!(universe.census().count(id), 1, "{} at generation {}", id, generation);
+            universe.tick();
+        }
+    }
+}
+
+#[test]
+pub fn test_objects_across_the_edge_of_a_torus() {
+    let mut universe = Universe::new(16, 16);
+    place(&mut universe, "block", 15, 15, Rotation::None, false);
+
+    let census = universe.census();
+    assert_eq!(census.len(), 1);
+    assert_eq!(census.objects()[0].name, Some("block"));
+    assert_eq!(census.objects()[0].bounding_box, Rect::new(15, 15, 2, 2));
+}
+
+#[test]
+pub fn test_soup_census() {
+    let mut universe = Universe::new(64, 64);
+    universe.randomize_with(2024, 0.35, Some(Rect::new(16, 16, 32, 32)));
+    for _ in 0..2000 {
+        universe.tick();
+    }
+
+    let census = universe.census();
+    let counts = census.counts();
+    assert_eq!(counts.values().sum::<usize>(), census.len());
+    assert_eq!(
+        census.objects().iter().map(|object| object.population).sum::<usize>() as u64,
+        wasm_game_of_life::Engine::population(&universe)
+    );
+    assert!(counts.contains_key("block") || counts.contains_key("blinker"), "{:?}", counts);
+}
+
+#[test]
+pub fn test_other_rules_are_unknown() {
+    // Under B3/S12 a block's cells, with three neighbours each, all die
+    let mut universe = Universe::with_rule(16, 16, Rule::parse("B3/S12").unwrap());
+    place(&mut universe, "block", 4, 4, Rotation::None, false);
+
+    let census = universe.census();
+    assert_eq!(census.len(), 1);
+    assert_eq!(census.count("block"), 0);
+    assert_eq!(census.counts().get("unknown"), Some(&1));
+}
+
+// Objects keep their shape across edges that mirror the other coordinate
+#[test]
+pub fn test_objects_across_twisted_edges() {
+    let hwss = library_pattern("hwss").unwrap();
+    for topology in [Topology::KleinBottle, Topology::CrossSurface] {
+        for offset in -6..=1 {
+            for (top, left) in [(offset, 10), (16 + offset, 10), (10, offset), (10, 16 + offset)] {
+                let mut universe = Universe::with_rule_and_topology(24, 16, Rule::conway(), topology);
+                for (row, column) in hwss.live_cells() {
+                    Grid::set_cell(&mut universe, top + row as i64, left + column as i64, true);
+                }
+
+                let census = universe.census();
+                assert_eq!(census.count("hwss"), 1, "{:?} at ({}, {})", topology, top, left);
+                assert_eq!(census.objects()[0].population, hwss.population());
+            }
+        }
+    }
+}
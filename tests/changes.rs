@@ -0,0 +1,57 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{Rect, Rule, Universe};
+
+fn blinker() -> Universe {
+    let mut universe = Universe::new(5, 5);
+    for col in 1..4 {
+        universe.toggle_cell(2, col);
+    }
+    universe
+}
+
+#[test]
+pub fn test_tick_records_changes() {
+    let mut universe = blinker();
+    universe.tick();
+    assert!(universe.changes().is_none());
+
+    universe.set_change_tracking(true);
+    universe.tick();
+
+    let changes = universe.changes().unwrap();
+    assert_eq!(changes.births().collect::<Vec<_>>(), vec![(2, 1), (2, 3)]);
+    assert_eq!(changes.deaths().collect::<Vec<_>>(), vec![(1, 2), (3, 2)]);
+    assert_eq!(
+        changes.dirty_rects(),
+        vec![Rect::new(1, 2, 1, 1), Rect::new(2, 1, 1, 1), Rect::new(2, 3, 1, 1), Rect::new(3, 2, 1, 1)]);
+}
+
+#[test]
+pub fn test_merge_gives_net_changes() {
+    let mut universe = blinker();
+    universe.set_change_tracking(true);
+
+    universe.tick();
+    let mut changes = universe.changes().unwrap().clone();
+    universe.tick();
+    changes.merge(universe.changes().unwrap());
+
+    // A blinker is back where it started after two generations
+    assert!(changes.is_empty());
+}
+
+#[test]
+pub fn test_dirty_rects_merge_rows() {
+    // Under Seeds (B2/S), a vertical domino dies and gives birth on both
+    // sides, changing a solid 3×2 block of cells
+    let mut universe = Universe::with_rule(6, 6, Rule::parse("B2/S").unwrap());
+    universe.set_change_tracking(true);
+    universe.toggle_cell(2, 2);
+    universe.toggle_cell(3, 2);
+    universe.tick();
+
+    let changes = universe.changes().unwrap();
+    assert_eq!(changes.birth_count(), 4);
+    assert_eq!(changes.death_count(), 2);
+    assert_eq!(changes.dirty_rects(), vec![Rect::new(2, 1, 3, 2)]);
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "image")]
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+extern crate wasm_game_of_life;
+use wasm_game_of_life::parse_rle;
+
+fn life(args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_life")).args(args).output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+pub fn test_until_stable() {
+    let (success, stdout, _) = life(&["patterns/blinker.rle", "--until-stable"]);
+    assert!(success);
+    assert!(stdout.contains("generation: 2\n"), "{}", stdout);
+    assert!(stdout.contains("population: 3\n"), "{}", stdout);
+    assert!(stdout.contains("period: 2\n"), "{}", stdout);
+}
+
+#[test]
+pub fn test_json() {
+    let (success, stdout, _) = life(&["patterns/glider.rle", "-u", "-j", "-s", "20x20"]);
+    assert!(success);
+    assert_eq!(
+        stdout,
+        "{\"generation\":4,\"population\":5,\"bounding_box\":{\"top\":9,\"left\":9,\"width\":3,\"height\":3},\
+         \"period\":4,\"stability\":\"translating\",\"since\":0,\"displacement\":{\"dx\":1,\"dy\":1}}\n"
+    );
+}
+
+#[test]
+pub fn test_output() {
+    let path = env::temp_dir().join(format!("life-cli-{}.rle", std::process::id()));
+    let (success, _, _) = life(&[
+        "patterns/glider.rle",
+        "--generations",
+        "2",
+        "--rule",
+        "B36/S23",
+        "--output",
+        path.to_str().unwrap(),
+    ]);
+    assert!(success);
+
+    let written = parse_rle(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(written.live_cells().count(), 5);
+    assert_eq!(written.rule.unwrap().to_string(), "B36/S23");
+    assert_eq!(written.name.as_deref(), Some("Glider"));
+    assert!(written.comments.contains(&String::from("Generation 2")));
+}
+
+#[test]
+pub fn test_images() {
+    let png = env::temp_dir().join(format!("life-cli-{}.png", std::process::id()));
+    let gif = env::temp_dir().join(format!("life-cli-{}.gif", std::process::id()));
+    let (success, _, _) = life(&[
+        "patterns/blinker.rle",
+        "-g",
+        "3",
+        "--png",
+        png.to_str().unwrap(),
+        "--gif",
+        gif.to_str().unwrap(),
+    ]);
+    assert!(success);
+
+    let png_bytes = fs::read(&png).unwrap();
+    let gif_bytes = fs::read(&gif).unwrap();
+    fs::remove_file(&png).unwrap();
+    fs::remove_file(&gif).unwrap();
+    assert!(png_bytes.starts_with(b"\x89PNG"));
+    assert!(gif_bytes.starts_with(b"GIF89a"));
+    // The starting generation and three more
+    assert_eq!(gif_bytes.windows(2).filter(|bytes| *bytes == [0x21, 0xF9]).count(), 4);
+}
+
+#[test]
+pub fn test_errors() {
+    let (success, _, stderr) = life(&["patterns/blinker.rle", "--topology", "moebius"]);
+    assert!(!success);
+    assert!(stderr.contains("unknown topology moebius"), "{}", stderr);
+
+    let (success, _, stderr) = life(&["does-not-exist.rle"]);
+    assert!(!success);
+    assert!(stderr.contains("couldn't read does-not-exist.rle"), "{}", stderr);
+
+    let (success, _, stderr) = life(&["patterns/gosper-glider-gun.rle", "--size", "10x10"]);
+    assert!(!success);
+    assert!(stderr.contains("doesn't fit"), "{}", stderr);
+}
+
+#[test]
+pub fn test_universe_too_large() {
+    let output = Command::new(env!("CARGO_BIN_EXE_life"))
+        .args(["patterns/blinker.rle", "--size", "70000x70000"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("universe too large"));
+
+    // The margin alone would take it past u32::MAX columns
+    let path = env::temp_dir().join(format!("life-cli-wide-{}.rle", std::process::id()));
+    fs::write(&path, "x = 4294967290, y = 0\n!\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_life")).arg(&path).output().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("universe too large"));
+}
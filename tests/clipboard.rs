@@ -0,0 +1,75 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, parse_rle, write_rle, Engine, Rect, Rotation, Universe};
+
+#[test]
+pub fn test_copy_region() {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 4, 4);
+
+    // The region is bigger than the glider, and the copy keeps the margin
+    let copy = universe.copy_region(Rect::new(3, 3, 5, 4));
+    assert_eq!((copy.width(), copy.height()), (5, 4));
+    assert_eq!(copy.live_cells().collect::<Vec<_>>(), [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+
+    // Regions past the edge are clipped
+    let copy = universe.copy_region(Rect::new(14, 14, 10, 10));
+    assert_eq!((copy.width(), copy.height()), (2, 2));
+    assert_eq!(copy.population(), 0);
+}
+
+// Regions can reach past the largest row and column a u32 holds
+#[test]
+pub fn test_huge_regions() {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 4, 4);
+
+    assert_eq!(universe.cells_in_rect(Rect::new(u32::MAX, 0, 2, 2)).count(), 0);
+    assert_eq!(universe.cells_in_rect(Rect::new(0, 0, u32::MAX, u32::MAX)).count(), 5);
+
+    let copy = universe.copy_region(Rect::new(4, 4, u32::MAX, u32::MAX));
+    assert_eq!((copy.width(), copy.height()), (12, 12));
+    assert_eq!(copy.population(), 5);
+
+    universe.randomize_with(1, 1.0, Some(Rect::new(1, 1, u32::MAX, 2)));
+    assert_eq!(universe.cells_in_rect(Rect::new(1, 0, 16, 2)).count(), 30);
+
+    universe.clear_region(Rect::new(u32::MAX, u32::MAX, u32::MAX, u32::MAX));
+    universe.clear_region(Rect::new(1, 1, u32::MAX, u32::MAX));
+    assert_eq!(Engine::population(&universe), 0);
+
+    assert_eq!(Rect::new(u32::MAX, 3, 2, 2).bottom(), u32::MAX);
+    assert_eq!(Rect::new(4, 4, u32::MAX, 1).clipped(16, 16), Rect::new(4, 4, 12, 1));
+    assert!(Rect::new(20, 0, 5, 5).clipped(16, 16).is_empty());
+}
+
+#[test]
+pub fn test_cut_and_paste() {
+    let mut universe = Universe::new(16, 16);
+    universe.set_history_budget(1 << 20);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 4, 4);
+    let before = format!("{}", universe);
+
+    let cut = universe.cut_region(Rect::new(4, 4, 3, 3));
+    assert_eq!(Engine::population(&universe), 0);
+    assert_eq!(cut.population(), 5);
+
+    universe.stamp(&cut, 10, 2, Rotation::Clockwise90, false);
+    assert_eq!(Engine::population(&universe), 5);
+    assert!(universe.cell_at(10, 2));
+    assert!(!universe.cell_at(4, 5));
+
+    assert!(universe.undo());
+    assert!(universe.undo());
+    assert_eq!(format!("{}", universe), before);
+}
+
+#[test]
+pub fn test_clipboard_round_trips_through_rle() {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("beehive").unwrap(), 2, 2);
+
+    let copy = universe.copy_region(Rect::new(1, 1, 6, 5));
+    let pasted = parse_rle(&write_rle(&copy)).unwrap();
+    assert_eq!((pasted.width(), pasted.height()), (6, 5));
+    assert_eq!(pasted.live_cells().collect::<Vec<_>>(), copy.live_cells().collect::<Vec<_>>());
+}
@@ -0,0 +1,87 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, BooleanOp, Engine, Pattern, Topology, Universe};
+
+mod common;
+use common::live_cells;
+
+// A horizontal and a vertical domino that overlap in (1, 1)
+fn pair() -> (Universe, Universe) {
+    let mut first = Universe::new(4, 4);
+    first.set_cell(1, 0, true);
+    first.set_cell(1, 1, true);
+
+    let mut second = Universe::new(4, 4);
+    second.set_cell(1, 1, true);
+    second.set_cell(2, 1, true);
+    (first, second)
+}
+
+#[test]
+pub fn test_operations() {
+    let expected = [
+        (BooleanOp::Union, vec![(1, 0), (1, 1), (2, 1)]),
+        (BooleanOp::Intersection, vec![(1, 1)]),
+        (BooleanOp::Difference, vec![(1, 0)]),
+        (BooleanOp::Xor, vec![(1, 0), (2, 1)]),
+    ];
+
+    for (op, cells) in expected.iter() {
+        let (mut first, second) = pair();
+        first.combine(&second, 0, 0, *op);
+        assert_eq!(live_cells(&first), *cells, "{:?}", op);
+    }
+}
+
+#[test]
+pub fn test_offsets_wrap_or_clip() {
+    let (mut first, second) = pair();
+    first.combine(&second, -2, 3, BooleanOp::Union);
+    assert_eq!(live_cells(&first), [(0, 0), (1, 0), (1, 1), (3, 0)]);
+
+    let mut bounded = Universe::with_topology(4, 4, Topology::Bounded);
+    bounded.combine(&second, -2, 2, BooleanOp::Union);
+    assert_eq!(live_cells(&bounded), [(0, 3)]);
+}
+
+#[test]
+pub fn test_intersection_masks() {
+    let mut universe = Universe::new(16, 16);
+    universe.randomize_with(5, 0.5, None);
+    let before = universe.copy_region(wasm_game_of_life::Rect::new(4, 4, 8, 8));
+
+    let mut mask = Pattern::new(8, 8);
+    for row in 0..8 {
+        for column in 0..8 {
+            mask.set_cell(row, column, true);
+        }
+    }
+    universe.combine_pattern(&mask, 4, 4, BooleanOp::Intersection);
+
+    assert_eq!(Engine::population(&universe), before.population() as u64);
+    let after = universe.copy_region(wasm_game_of_life::Rect::new(4, 4, 8, 8));
+    assert_eq!(after.live_cells().collect::<Vec<_>>(), before.live_cells().collect::<Vec<_>>());
+}
+
+#[test]
+pub fn test_diff_generations() {
+    let mut universe = Universe::new(8, 8);
+    universe.load_pattern(&library_pattern("blinker").unwrap(), 3, 2);
+    let mut next = Universe::new(8, 8);
+    next.load_pattern(&library_pattern("blinker").unwrap(), 3, 2);
+    next.tick();
+
+    universe.combine(&next, 0, 0, BooleanOp::Xor);
+    assert_eq!(live_cells(&universe), [(2, 3), (3, 2), (3, 4), (4, 3)]);
+}
+
+#[test]
+pub fn test_overlay_keeps_what_is_underneath() {
+    let mut universe = Universe::new(8, 8);
+    universe.set_cell(0, 0, true);
+    universe.set_history_budget(1 << 20);
+    universe.combine_pattern(&library_pattern("block").unwrap(), 0, 1, BooleanOp::Union);
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2)]);
+
+    assert!(universe.undo());
+    assert_eq!(live_cells(&universe), [(0, 0)]);
+}
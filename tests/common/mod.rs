@@ -0,0 +1,21 @@
+// Helpers shared by the integration tests. Each test file only uses some of
+// them.
+#![allow(dead_code)]
+
+use wasm_game_of_life::{Pattern, Rule, Topology, Universe};
+
+// The live cells, in row-major order
+pub fn live_cells(universe: &Universe) -> Vec<(u32, u32)> {
+    universe.live_cells().collect()
+}
+
+pub fn pattern_cells(pattern: &Pattern) -> Vec<(u32, u32)> {
+    pattern.live_cells().collect()
+}
+
+// A soup from a fixed seed, so failures can be reproduced
+pub fn soup(width: u32, height: u32, rule: Rule, topology: Topology, seed: u64, density: f64) -> Universe {
+    let mut universe = Universe::with_rule_and_topology(width, height, rule, topology);
+    universe.randomize_with(seed, density, None);
+    universe
+}
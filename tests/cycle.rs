@@ -0,0 +1,122 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, AutoPause, Stability, Universe, DEFAULT_CYCLE_WINDOW};
+
+fn run(id: &str, generations: u32) -> Stability {
+    let mut universe = Universe::new(64, 64);
+    universe.load_pattern(&library_pattern(id).unwrap(), 20, 20);
+    universe.set_cycle_detection(true);
+    for _ in 0..generations {
+        universe.tick();
+    }
+    universe.stability().unwrap()
+}
+
+#[test]
+pub fn test_detection_is_off_by_default() {
+    let mut universe = Universe::new(8, 8);
+    universe.tick();
+    assert_eq!(universe.stability(), None);
+}
+
+#[test]
+pub fn test_still_life() {
+    assert_eq!(run("beehive", 1), Stability::Static { since: 0 });
+}
+
+#[test]
+pub fn test_oscillators() {
+    assert_eq!(run("blinker", 1), Stability::Evolving);
+    assert_eq!(run("blinker", 2), Stability::Oscillating { period: 2, since: 0 });
+    assert_eq!(run("pulsar", 3), Stability::Oscillating { period: 3, since: 0 });
+    assert_eq!(run("pentadecathlon", 20).period(), Some(15));
+}
+
+#[test]
+pub fn test_spaceships() {
+    assert_eq!(run("glider", 4), Stability::Translating { period: 4, dx: 1, dy: 1, since: 0 });
+    assert_eq!(run("lwss", 4), Stability::Translating { period: 4, dx: -2, dy: 0, since: 0 });
+}
+
+#[test]
+pub fn test_dying_out() {
+    let stability = run("diehard", 140);
+    assert_eq!(stability, Stability::Static { since: 130 });
+    assert!(stability.is_settled());
+}
+
+#[test]
+pub fn test_edits_restart_detection() {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("block").unwrap(), 4, 4);
+    universe.set_cycle_detection(true);
+    universe.tick();
+    assert!(universe.stability().unwrap().is_settled());
+
+    universe.load_pattern(&library_pattern("blinker").unwrap(), 10, 4);
+    assert_eq!(universe.stability(), Some(Stability::Evolving));
+    universe.tick();
+    assert_eq!(universe.stability(), Some(Stability::Evolving));
+    universe.tick();
+    assert_eq!(universe.stability(), Some(Stability::Oscillating { period: 2, since: 1 }));
+}
+
+#[test]
+pub fn test_auto_pause_lets_a_glider_run_on() {
+    let mut universe = Universe::new(32, 32);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 4, 4);
+    universe.set_cycle_detection(true);
+    let mut auto_pause = AutoPause::new();
+
+    let mut pauses = Vec::new();
+    for _ in 0..40 {
+        universe.tick();
+        if auto_pause.should_pause(universe.stability(), universe.generation()) {
+            pauses.push(universe.generation());
+        }
+    }
+    assert_eq!(pauses, vec![4]);
+    assert_eq!(universe.generation(), 40);
+    assert_eq!(auto_pause.paused_at(), Some(4));
+
+    // Once an edit restarts the search, settling again pauses again
+    universe.toggle_cell(20, 20);
+    assert!(!auto_pause.should_pause(universe.stability(), universe.generation()));
+    assert_eq!(auto_pause.paused_at(), None);
+    let mut paused = false;
+    for _ in 0..40 {
+        universe.tick();
+        paused |= auto_pause.should_pause(universe.stability(), universe.generation());
+    }
+    assert!(paused);
+}
+
+#[test]
+pub fn test_window_limits_the_period_found() {
+    let mut universe = Universe::new(64, 64);
+    universe.load_pattern(&library_pattern("pulsar").unwrap(), 20, 20);
+    universe.set_cycle_detection_window(2);
+    universe.set_cycle_detection(true);
+    for _ in 0..12 {
+        universe.tick();
+    }
+    assert_eq!(universe.stability(), Some(Stability::Evolving));
+
+    universe.set_cycle_detection_window(DEFAULT_CYCLE_WINDOW);
+    universe.toggle_cell(0, 0);
+    universe.toggle_cell(0, 0);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.stability().unwrap().period(), Some(3));
+}
+
+#[test]
+pub fn test_settled_result_is_kept_until_an_edit() {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("blinker").unwrap(), 4, 4);
+    universe.set_cycle_detection(true);
+    for _ in 0..100 {
+        universe.tick();
+    }
+    assert_eq!(universe.stability(), Some(Stability::Oscillating { period: 2, since: 0 }));
+}
@@ -0,0 +1,150 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{parse_pattern, parse_rle, PatternFormat, Rule, Universe};
+
+mod common;
+use common::pattern_cells;
+
+const GLIDER_CELLS: &[(u32, u32)] = &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+
+#[test]
+pub fn test_detect_and_parse() {
+    let inputs = [
+        (PatternFormat::Plaintext, "!Name: Glider\n.O.\n..O\nOOO\n"),
+        (PatternFormat::Life105, "#Life 1.05\n#D Glider\n#N\n#P -1 -1\n.*\n..*\n***\n"),
+        (PatternFormat::Life106, "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n"),
+        (PatternFormat::Macrocell, "[M2] (golly 4.0)\n#R B3/S23\n.*$..*$***$\n"),
+        (PatternFormat::Rle, "x = 3, y = 3\nbo$2bo$3o!\n"),
+    ];
+
+    for (format, input) in inputs.iter() {
+        assert_eq!(PatternFormat::detect(input), *format);
+        let pattern = parse_pattern(input).unwrap();
+        assert_eq!(pattern_cells(&pattern), GLIDER_CELLS, "{:?}", format);
+    }
+}
+
+#[test]
+pub fn test_round_trips() {
+    let gun = parse_rle("#N Gosper glider gun
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!").unwrap();
+
+    let formats = [
+        PatternFormat::Rle,
+        PatternFormat::Plaintext,
+        PatternFormat::Life105,
+        PatternFormat::Life106,
+        PatternFormat::Macrocell,
+    ];
+    for format in formats.iter() {
+        let written = format.write(&gun);
+        assert_eq!(PatternFormat::detect(&written), *format);
+
+        let read = parse_pattern(&written).unwrap();
+        assert_eq!(pattern_cells(&read), pattern_cells(&gun), "{:?}", format);
+    }
+}
+
+#[test]
+pub fn test_macrocell_loads_into_universe() {
+    let pattern = parse_pattern("[M2]\n#R B36/S23\n$$..*$\n4 0 1 1 0\n").unwrap();
+    assert_eq!(pattern.rule, Some(Rule::parse("B36/S23").unwrap()));
+    assert_eq!(pattern.population(), 2);
+
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&pattern, 0, 0);
+    assert!(universe.cell_at(0, 8));
+    assert!(universe.cell_at(8, 0));
+}
+
+#[test]
+pub fn test_errors() {
+    let error = parse_pattern("!Name: Bad\n.O.\n.X.\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 2));
+
+    let error = parse_pattern("#Life 1.06\n0 0\n1 two\n").unwrap_err();
+    assert_eq!(error.line, 3);
+
+    let error = parse_pattern("[M2]\n.*$\n4 1 2 0 0\n").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 5));
+}
+
+// A leaf followed by nodes from level 4 up to `levels`, each made of four
+// copies of the one before.
+fn doubling_macrocell(leaf: &str, levels: u32) -> String {
+    let mut input = format!("[M2]\n{}\n", leaf);
+    for level in 4..=levels {
+        let child = level - 3;
+        input.push_str(&format!("{} {} {} {} {}\n", level, child, child, child, child));
+    }
+    input
+}
+
+#[test]
+pub fn test_macrocell_size_is_checked_before_expanding() {
+    let error = parse_pattern(&doubling_macrocell("*$", 40)).unwrap_err();
+    assert_eq!(error.line, 39);
+    assert!(error.message.contains("live cells"), "{}", error.message);
+
+    // Empty nodes cost nothing however high they go
+    let pattern = parse_pattern(&doubling_macrocell("$", 60)).unwrap();
+    assert_eq!(pattern.population(), 0);
+
+    // Two cells too far apart for a pattern's coordinates
+    let mut input = String::from("[M2]\n*$\n");
+    for level in 4..40 {
+        input.push_str(&format!("{} {} 0 0 0\n", level, level - 3));
+    }
+    input.push_str("40 37 0 0 37\n");
+    let error = parse_pattern(&input).unwrap_err();
+    assert!(error.message.contains("too large"), "{}", error.message);
+}
+
+#[test]
+pub fn test_cells_too_far_apart() {
+    // Coordinates span more rows or columns than a pattern can count
+    let error = parse_pattern("#Life 1.06\n0 -9223372036854775808\n0 9223372036854775807\n").unwrap_err();
+    assert_eq!(error.line, 3);
+    assert!(error.message.contains("too large"), "{}", error.message);
+    assert!(parse_pattern("#Life 1.06\n-2147483648 0\n2147483648 0\n").is_err());
+    let pattern = parse_pattern("#Life 1.06\n-2147483648 0\n2147483646 0\n").unwrap();
+    assert_eq!(pattern.width(), u32::MAX);
+
+    let error = parse_pattern("#Life 1.05\n#P 0 0\n*\n#P 5000000000 0\n*\n").unwrap_err();
+    assert_eq!(error.line, 5);
+}
+
+#[test]
+pub fn test_headers_after_blank_lines() {
+    let inputs = [
+        (PatternFormat::Life106, "\n#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n"),
+        (PatternFormat::Life105, "\n  \n#Life 1.05\n#P -1 -1\n.*\n..*\n***\n"),
+        (PatternFormat::Macrocell, "\n\n[M2] (golly 4.0)\n.*$..*$***$\n"),
+    ];
+    for (format, input) in inputs.iter() {
+        assert_eq!(PatternFormat::detect(input), *format);
+        let pattern = parse_pattern(input).unwrap();
+        assert_eq!(pattern_cells(&pattern), GLIDER_CELLS, "{:?}", format);
+    }
+
+    let error = parse_pattern("\n#Life 1.06\n0 0\nnonsense\n").unwrap_err();
+    assert_eq!(error.line, 4);
+}
+
+#[test]
+pub fn test_life_105_keeps_metadata() {
+    let mut glider = parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    glider.name = Some(String::from("Glider"));
+    glider.author = Some(String::from("Richard K. Guy"));
+    glider.comments = vec![String::from("The smallest spaceship")];
+
+    let written = PatternFormat::Life105.write(&glider);
+    assert!(written.contains("#D Name: Glider\n"), "{}", written);
+
+    let read = parse_pattern(&written).unwrap();
+    assert_eq!(read.name, glider.name);
+    assert_eq!(read.author, glider.author);
+    assert_eq!(read.comments, glider.comments);
+    assert_eq!(pattern_cells(&read), GLIDER_CELLS);
+}
@@ -0,0 +1,110 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{parse_rle, Engine, EngineError, HashLife, Rule, Universe, MAX_STEP_POWER};
+
+mod common;
+use common::pattern_cells;
+
+#[test]
+pub fn test_matches_universe() {
+    let r_pentomino = parse_rle("x = 3, y = 3\nb2o$2o$bo!").unwrap();
+
+    let mut universe = Universe::new(128, 128);
+    universe.load_pattern(&r_pentomino, 62, 62);
+    let mut hashlife = HashLife::from_pattern(&r_pentomino).unwrap();
+
+    for generations in [1, 2, 5, 13, 79].iter().cloned() {
+        for _ in 0..generations {
+            universe.tick();
+        }
+        hashlife.step(generations);
+
+        assert_eq!(hashlife.generation(), universe.generation());
+        assert_eq!(Engine::population(&hashlife), Engine::population(&universe));
+        assert_eq!(pattern_cells(&hashlife.to_pattern().unwrap()), pattern_cells(&universe.to_pattern()));
+    }
+}
+
+#[test]
+pub fn test_other_rules() {
+    let replicator = parse_rle("x = 3, y = 3, rule = B36/S23\nb2o$obo$2o!").unwrap();
+
+    let mut universe = Universe::with_rule(64, 64, Rule::parse("B36/S23").unwrap());
+    universe.load_pattern(&replicator, 30, 30);
+    let mut hashlife = HashLife::from_pattern(&replicator).unwrap();
+
+    for _ in 0..12 {
+        universe.tick();
+    }
+    hashlife.step(12);
+
+    assert_eq!(pattern_cells(&hashlife.to_pattern().unwrap()), pattern_cells(&universe.to_pattern()));
+}
+
+#[test]
+pub fn test_glider_travels() {
+    let glider = parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    let mut hashlife = HashLife::from_pattern(&glider).unwrap();
+
+    // A glider moves one cell diagonally every 4 generations
+    hashlife.step_power_of_two(20).unwrap();
+    assert_eq!(hashlife.generation(), 1 << 20);
+    assert_eq!(Engine::population(&hashlife), 5);
+
+    let offset = (1 << 20) / 4;
+    assert!(hashlife.cell_at(offset, offset + 1));
+    assert!(hashlife.cell_at(offset + 2, offset + 2));
+    assert!(!hashlife.cell_at(0, 1));
+}
+
+#[test]
+pub fn test_rejects_b0() {
+    let rule = Rule::parse("B0/S8").unwrap();
+    let error = HashLife::new(rule).err().unwrap();
+    assert_eq!(error, EngineError::UnsupportedRule(rule));
+    assert!(error.to_string().contains("B0/S8"));
+
+    let pattern = parse_rle("x = 1, y = 1, rule = B01/S8\no!").unwrap();
+    assert!(HashLife::from_pattern(&pattern).is_err());
+}
+
+#[test]
+pub fn test_huge_steps_are_refused() {
+    let glider = parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    let mut hashlife = HashLife::from_pattern(&glider).unwrap();
+
+    assert_eq!(hashlife.step_power_of_two(62), Err(EngineError::OutOfRange));
+    assert_eq!(hashlife.step_power_of_two(63), Err(EngineError::OutOfRange));
+    assert_eq!(hashlife.generation(), 0);
+    assert!(hashlife.cell_at(0, 1));
+    assert_eq!(pattern_cells(&hashlife.to_pattern().unwrap()), pattern_cells(&glider));
+
+    // The glider moves 2^(k-2) cells, so the biggest step still fits
+    hashlife.step_power_of_two(MAX_STEP_POWER).unwrap();
+    assert_eq!(hashlife.generation(), 1 << MAX_STEP_POWER);
+    assert_eq!(Engine::population(&hashlife), 5);
+    let offset = 1i64 << (MAX_STEP_POWER - 2);
+    assert!(hashlife.cell_at(offset, offset + 1));
+
+    // Stepping a long way on reaches the edge, and stops there
+    let mut failed = false;
+    for _ in 0..8 {
+        if hashlife.step_power_of_two(MAX_STEP_POWER).is_err() {
+            failed = true;
+            break;
+        }
+    }
+    assert!(failed);
+    assert_eq!(Engine::population(&hashlife), 5);
+    assert_eq!(hashlife.to_pattern().unwrap().population(), 5);
+
+    hashlife.step(u64::MAX);
+    assert_eq!(Engine::population(&hashlife), 5);
+}
+
+#[test]
+pub fn test_r_pentomino_settles() {
+    let r_pentomino = parse_rle("x = 3, y = 3\nb2o$2o$bo!").unwrap();
+    let mut hashlife = HashLife::from_pattern(&r_pentomino).unwrap();
+    hashlife.step(1103);
+    assert_eq!(Engine::population(&hashlife), 116);
+}
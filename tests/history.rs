@@ -0,0 +1,120 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Engine, HistoryKind, Universe};
+
+fn glider_universe() -> Universe {
+    let mut universe = Universe::new(16, 16);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 1, 1);
+    universe
+}
+
+#[test]
+pub fn test_history_is_off_by_default() {
+    let mut universe = glider_universe();
+    universe.tick();
+    assert!(!universe.can_undo());
+    assert!(!universe.undo());
+    assert_eq!(universe.history_memory(), 0);
+}
+
+#[test]
+pub fn test_step_back_and_forward() {
+    let start = glider_universe();
+    let mut universe = glider_universe();
+    universe.set_history_budget(1 << 20);
+
+    universe.tick();
+    let after_one = format!("{}", universe);
+    universe.tick();
+    assert_eq!(universe.generation(), 2);
+
+    assert!(universe.step_back());
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(format!("{}", universe), after_one);
+    assert!(universe.step_back());
+    assert_eq!(universe, start);
+    assert_eq!(universe.generation(), 0);
+    assert!(!universe.step_back());
+
+    assert!(universe.redo());
+    assert!(universe.redo());
+    assert!(!universe.redo());
+    assert_eq!(universe.generation(), 2);
+}
+
+#[test]
+pub fn test_undo_edits() {
+    let mut universe = glider_universe();
+    universe.set_history_budget(1 << 20);
+    let start = format!("{}", universe);
+
+    universe.tick();
+    universe.toggle_cell(10, 10);
+    universe.clear();
+    assert_eq!(Engine::population(&universe), 0);
+    assert_eq!(universe.generation(), 0);
+
+    // Step back only undoes ticks
+    assert_eq!(universe.last_change(), Some(HistoryKind::Edit));
+    assert!(!universe.step_back());
+
+    assert!(universe.undo());
+    assert_eq!(universe.generation(), 1);
+    assert!(universe.cell_at(10, 10));
+    assert!(universe.undo());
+    assert!(!universe.cell_at(10, 10));
+    assert_eq!(universe.last_change(), Some(HistoryKind::Tick));
+    assert!(universe.undo());
+    assert_eq!(format!("{}", universe), start);
+    assert!(!universe.can_undo());
+
+    // A new edit after undoing drops what could have been redone
+    assert!(universe.redo());
+    universe.randomize_with(1, 0.5, None);
+    assert!(!universe.can_redo());
+    assert!(universe.undo());
+    assert_eq!(universe.generation(), 1);
+}
+
+#[test]
+pub fn test_budget_forgets_oldest() {
+    let mut universe = glider_universe();
+    universe.set_history_budget(1 << 20);
+    universe.tick();
+    let one_state = universe.history_memory();
+    assert!(one_state > 0);
+
+    universe.set_history_budget(one_state * 3);
+    for _ in 0..10 {
+        universe.tick();
+    }
+    assert!(universe.history_memory() <= one_state * 3);
+
+    let mut undone = 0;
+    while universe.undo() {
+        undone += 1;
+    }
+    assert_eq!(undone, 3);
+    assert_eq!(universe.generation(), 8);
+
+    universe.set_history_budget(0);
+    assert!(!universe.can_redo());
+    assert_eq!(universe.history_memory(), 0);
+}
+
+#[test]
+pub fn test_undo_stays_within_budget() {
+    let mut universe = glider_universe();
+    universe.set_history_budget(1 << 20);
+    universe.tick();
+    let small_state = universe.history_memory();
+
+    // Undoing a resize keeps the bigger universe to redo, which mustn't go
+    // over budget
+    universe.set_history_budget(small_state * 3);
+    universe.resize(256, 256);
+    assert!(universe.history_memory() <= small_state * 3);
+    assert!(universe.undo());
+    assert_eq!(universe.width(), 16);
+    assert!(universe.history_memory() <= small_state * 3);
+    assert!(!universe.can_redo());
+}
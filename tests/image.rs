@@ -0,0 +1,172 @@
+#![cfg(feature = "image")]
+
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, write_gif, write_png, Color, GifWriter, ImageError, ImageStyle, Universe};
+
+const GRID: [u8; 3] = [0xCC, 0xCC, 0xCC];
+const DEAD: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const ALIVE: [u8; 3] = [0x00, 0x00, 0x00];
+
+fn glider(width: u32, height: u32) -> Universe {
+    let mut universe = Universe::new(width, height);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 1, 1);
+    universe
+}
+
+// The decoded picture as RGB pixels, row by row
+fn decode_png(png: &[u8]) -> (u32, u32, Vec<[u8; 3]>) {
+    let mut decoder = png::Decoder::new(png);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().unwrap();
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    let pixels = buffer[..info.buffer_size()].chunks(3).map(|rgb| [rgb[0], rgb[1], rgb[2]]).collect();
+    (info.width, info.height, pixels)
+}
+
+#[test]
+pub fn test_png() {
+    let universe = glider(4, 5);
+    let (width, height, pixels) = decode_png(&write_png(&universe, &ImageStyle::default()).unwrap());
+
+    // Laid out like the canvas: a grid line, then 10 pixels of cell
+    assert_eq!((width, height), (45, 56));
+    let pixel = |x: u32, y: u32| pixels[(y * width + x) as usize];
+    assert_eq!(pixel(0, 0), GRID);
+    assert_eq!(pixel(11, 30), GRID);
+    assert_eq!(pixel(1, 1), DEAD);
+    assert_eq!(pixel(23, 12), ALIVE);
+    assert_eq!(pixel(43, 32), ALIVE);
+    assert_eq!(pixel(44, 55), GRID);
+
+    let live = pixels.iter().filter(|pixel| **pixel == ALIVE).count();
+    assert_eq!(live, 5 * 10 * 10);
+}
+
+#[test]
+pub fn test_png_style() {
+    let style = ImageStyle {
+        cell_size: 3,
+        grid_lines: false,
+        alive_color: Color::from_hex("#F80").unwrap(),
+        ..ImageStyle::default()
+    };
+    let mut universe = Universe::new(300, 200);
+    universe.randomize_with(3, 0.5, None);
+
+    let (width, height, pixels) = decode_png(&write_png(&universe, &style).unwrap());
+    assert_eq!((width, height), (900, 600));
+    for (row, column) in [(0, 0), (17, 250), (199, 299), (100, 3)] {
+        let expected = if universe.cell_at(row, column) { [0xFF, 0x88, 0x00] } else { DEAD };
+        for (dy, dx) in [(0, 0), (2, 2), (1, 2)] {
+            assert_eq!(pixels[((row * 3 + dy) * width + column * 3 + dx) as usize], expected);
+        }
+    }
+    assert!(!pixels.contains(&GRID));
+}
+
+#[test]
+pub fn test_gif() {
+    let mut universe = glider(6, 6);
+    let gif = write_gif(&mut universe, 4, &ImageStyle::default(), 100).unwrap();
+    assert_eq!(universe.generation(), 4);
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(&gif[..]).unwrap();
+    assert_eq!((decoder.width(), decoder.height()), (67, 67));
+
+    let mut replay = glider(6, 6);
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!(frame.delay, 10);
+        let alive = |row: u32, column: u32| {
+            let offset = (((row * 11 + 5) * 67 + column * 11 + 5) * 4) as usize;
+            frame.buffer[offset..offset + 3] == ALIVE
+        };
+        for row in 0..6 {
+            for column in 0..6 {
+                assert_eq!(alive(row, column), replay.cell_at(row, column), "frame {}, ({}, {})", frames, row, column);
+            }
+        }
+        replay.tick();
+        frames += 1;
+    }
+    assert_eq!(frames, 5);
+}
+
+// Enough noise to fill the LZW table several times over
+#[test]
+pub fn test_gif_large_frame() {
+    let style = ImageStyle { cell_size: 1, grid_lines: false, ..ImageStyle::default() };
+    let mut universe = Universe::new(400, 300);
+    universe.randomize_with(11, 0.5, None);
+
+    let mut writer = GifWriter::new(style, 50);
+    writer.add_frame(&universe).unwrap();
+    assert_eq!(writer.frame_count(), 1);
+    let gif = writer.finish().unwrap();
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(&gif[..]).unwrap();
+    let frame = decoder.read_next_frame().unwrap().unwrap();
+    for (idx, index) in frame.buffer.iter().enumerate() {
+        let alive = universe.cell_at(idx as u32 / 400, idx as u32 % 400);
+        assert_eq!(*index, if alive { 2 } else { 1 });
+    }
+}
+
+#[test]
+pub fn test_too_large() {
+    // 11 pixels a cell and a grid line make a picture 77001 pixels wide,
+    // more than a GIF can record
+    let mut universe = glider(7000, 1);
+    let mut writer = GifWriter::new(ImageStyle::default(), 50);
+    assert_eq!(
+        writer.add_frame(&universe),
+        Err(ImageError::TooLarge { width: 77001, height: 12, limit: 65535 })
+    );
+    assert_eq!(writer.frame_count(), 0);
+    assert!(write_gif(&mut universe, 4, &ImageStyle::default(), 100).is_err());
+    assert_eq!(universe.generation(), 0);
+
+    // It fits in a PNG
+    let (width, height, _) = decode_png(&write_png(&universe, &ImageStyle::default()).unwrap());
+    assert_eq!((width, height), (77001, 12));
+
+    // Sizes are worked out without overflowing
+    let style = ImageStyle { cell_size: u32::MAX, ..ImageStyle::default() };
+    assert_eq!(style.image_size(u32::MAX, 1), ((1 << 32) * u32::MAX as u64 + 1, (1 << 32) + 1));
+    let error = write_png(&Universe::new(2, 2), &style).unwrap_err();
+    assert!(matches!(error, ImageError::TooLarge { width: 8589934593, .. }));
+    assert!(error.to_string().contains("8589934593x8589934593"));
+}
+
+#[test]
+pub fn test_frame_size() {
+    let mut writer = GifWriter::new(ImageStyle::default(), u32::MAX);
+    writer.add_frame(&glider(6, 6)).unwrap();
+    assert_eq!(
+        writer.add_frame(&glider(7, 6)),
+        Err(ImageError::FrameSize { width: 78, height: 67, first_width: 67, first_height: 67 })
+    );
+    assert_eq!(writer.frame_count(), 1);
+
+    let gif = writer.finish().unwrap();
+    let mut decoder = gif::DecodeOptions::new().read_info(&gif[..]).unwrap();
+    let frame = decoder.read_next_frame().unwrap().unwrap();
+    assert_eq!((frame.width, frame.height, frame.delay), (67, 67, u16::MAX));
+    assert!(decoder.read_next_frame().unwrap().is_none());
+}
+
+#[test]
+pub fn test_colors() {
+    assert_eq!(Color::from_hex("#CCCCCC"), Some(Color::rgb(0xCC, 0xCC, 0xCC)));
+    assert_eq!(Color::from_hex("#0a1"), Some(Color::rgb(0x00, 0xAA, 0x11)));
+    assert_eq!(Color::from_hex("CCCCCC"), None);
+    assert_eq!(Color::from_hex("#CCCCC"), None);
+    assert_eq!(Color::from_hex("#GGGGGG"), None);
+    assert_eq!(Color::rgb(0, 120, 255).to_string(), "#0078FF");
+}
@@ -0,0 +1,136 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Engine, Pattern, Rotation, SparseUniverse, Universe, LIBRARY};
+
+// Runs a pattern until it matches its starting shape, returning the period
+// and how far it moved.
+fn period_and_shift(pattern: &Pattern, limit: u32) -> Option<(u32, (i64, i64))> {
+    let mut universe = SparseUniverse::from_pattern(pattern).unwrap();
+    let start: Vec<(u32, u32)> = pattern.live_cells().collect();
+
+    for generation in 1..=limit {
+        universe.tick();
+        let shape = universe.to_pattern().unwrap();
+        if shape.live_cells().collect::<Vec<_>>() == start {
+            let top = universe.live_cells().map(|(row, _)| row).min().unwrap();
+            let left = universe.live_cells().map(|(_, column)| column).min().unwrap();
+            let start_top = start.iter().map(|(row, _)| *row as i64).min().unwrap();
+            let start_left = start.iter().map(|(_, column)| *column as i64).min().unwrap();
+            return Some((generation, (top - start_top, left - start_left)));
+        }
+    }
+    None
+}
+
+#[test]
+pub fn test_every_pattern_parses() {
+    for entry in LIBRARY.iter() {
+        let pattern = entry.pattern();
+        assert_eq!(pattern.name.as_deref(), Some(entry.name), "{}", entry.id);
+        assert!(pattern.population() > 0, "{}", entry.id);
+        assert_eq!(library_pattern(entry.id), Some(pattern.clone()));
+        assert_eq!(library_pattern(&entry.name.to_uppercase()), Some(pattern));
+    }
+
+    assert_eq!(library_pattern("no such pattern"), None);
+}
+
+#[test]
+pub fn test_oscillator_periods() {
+    let expected = [
+        ("block", 1),
+        ("beehive", 1),
+        ("loaf", 1),
+        ("boat", 1),
+        ("tub", 1),
+        ("ship", 1),
+        ("pond", 1),
+        ("barge", 1),
+        ("long-boat", 1),
+        ("blinker", 2),
+        ("toad", 2),
+        ("beacon", 2),
+        ("pulsar", 3),
+        ("pentadecathlon", 15),
+    ];
+
+    for (id, period) in expected.iter() {
+        let pattern = library_pattern(id).unwrap();
+        assert_eq!(period_and_shift(&pattern, 30), Some((*period, (0, 0))), "{}", id);
+    }
+}
+
+#[test]
+pub fn test_spaceships() {
+    let expected = [("glider", (1, 1)), ("lwss", (0, -2)), ("mwss", (0, -2)), ("hwss", (0, -2))];
+
+    for (id, shift) in expected.iter() {
+        let pattern = library_pattern(id).unwrap();
+        assert_eq!(period_and_shift(&pattern, 4), Some((4, *shift)), "{}", id);
+    }
+}
+
+#[test]
+pub fn test_methuselahs_and_guns() {
+    let mut diehard = SparseUniverse::from_pattern(&library_pattern("diehard").unwrap()).unwrap();
+    diehard.step(129);
+    assert!(diehard.population() > 0);
+    diehard.tick();
+    assert_eq!(diehard.population(), 0);
+
+    // After one period the gun is back to its starting shape, plus the
+    // glider it fired
+    let gun = library_pattern("gosper-glider-gun").unwrap();
+    let mut universe = SparseUniverse::from_pattern(&gun).unwrap();
+    universe.step(30);
+    assert_eq!(universe.population(), gun.population() as u64 + 5);
+}
+
+#[test]
+pub fn test_transformed() {
+    // .O.
+    // ..O
+    // OOO
+    let glider = library_pattern("glider").unwrap();
+    let cells = |pattern: &Pattern| pattern.live_cells().collect::<Vec<_>>();
+
+    assert_eq!(glider.transformed(Rotation::None, false), glider);
+    assert_eq!(cells(&glider.transformed(Rotation::Clockwise90, false)), [(0, 0), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    assert_eq!(cells(&glider.transformed(Rotation::Clockwise180, false)), [(0, 0), (0, 1), (0, 2), (1, 0), (2, 1)]);
+    assert_eq!(cells(&glider.transformed(Rotation::Clockwise270, false)), [(0, 1), (0, 2), (1, 0), (1, 2), (2, 2)]);
+    assert_eq!(cells(&glider.transformed(Rotation::None, true)), [(0, 1), (1, 0), (2, 0), (2, 1), (2, 2)]);
+
+    let lwss = library_pattern("lwss").unwrap();
+    let turned = lwss.transformed(Rotation::Clockwise90, true);
+    assert_eq!((turned.width(), turned.height()), (4, 5));
+    assert_eq!(turned.name, lwss.name);
+
+    let mut rotation = Rotation::None;
+    for _ in 0..4 {
+        rotation = rotation.next();
+    }
+    assert_eq!(rotation, Rotation::None);
+}
+
+#[test]
+pub fn test_stamp() {
+    let glider = library_pattern("glider").unwrap();
+    let mut universe = Universe::new(8, 8);
+    for column in 0..8 {
+        universe.set_cell(3, column, true);
+    }
+
+    universe.stamp(&glider, 2, 2, Rotation::Clockwise180, false);
+
+    // The stamp clears the dead cells under it
+    assert!(universe.cell_at(2, 2) && universe.cell_at(2, 3) && universe.cell_at(2, 4));
+    assert!(universe.cell_at(3, 2) && !universe.cell_at(3, 3) && !universe.cell_at(3, 4));
+    assert!(universe.cell_at(4, 3));
+    assert!(universe.cell_at(3, 1) && universe.cell_at(3, 5));
+    assert_eq!(Engine::population(&universe), 5 + 5);
+
+    // Stamps wrap around a torus like load_pattern
+    let mut universe = Universe::new(8, 8);
+    universe.stamp(&glider, 7, 7, Rotation::None, false);
+    assert!(universe.cell_at(7, 0));
+    assert!(universe.cell_at(1, 7));
+}
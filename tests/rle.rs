@@ -0,0 +1,84 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{parse_rle, write_rle, Rule, Universe, MAX_RLE_AREA, MAX_RLE_POPULATION};
+
+const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+#[test]
+pub fn test_parse() {
+    let pattern = parse_rle(GLIDER).unwrap();
+
+    assert_eq!(pattern.name.as_deref(), Some("Glider"));
+    assert_eq!(pattern.author.as_deref(), Some("Richard K. Guy"));
+    assert_eq!(pattern.comments.len(), 1);
+    assert_eq!(pattern.rule, Some(Rule::conway()));
+    assert_eq!((pattern.width(), pattern.height()), (3, 3));
+    assert_eq!(
+        pattern.live_cells().collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+}
+
+#[test]
+pub fn test_round_trip() {
+    let pattern = parse_rle(GLIDER).unwrap();
+    assert_eq!(write_rle(&pattern), GLIDER);
+
+    let gun = parse_rle("x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!").unwrap();
+    assert_eq!(parse_rle(&write_rle(&gun)), Ok(gun));
+}
+
+#[test]
+pub fn test_load_and_export() {
+    let pattern = parse_rle(GLIDER).unwrap();
+    let mut universe = Universe::new(8, 8);
+    universe.load_pattern(&pattern, 2, 3);
+
+    assert!(universe.cell_at(2, 4));
+    assert!(universe.cell_at(4, 3));
+    assert!(!universe.cell_at(0, 1));
+
+    let exported = universe.to_pattern();
+    assert_eq!(exported.live_cells().collect::<Vec<_>>(), pattern.live_cells().collect::<Vec<_>>());
+    assert_eq!(exported.rule, Some(Rule::conway()));
+}
+
+#[test]
+pub fn test_errors() {
+    let error = parse_rle("x = 3, y = 3\nbo$2bq$3o!").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 6));
+
+    let error = parse_rle("x = 3, y = three\nbo$2bo$3o!").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 12));
+
+    let error = parse_rle("x = 3, y = 3, rule = B3/S239\nbo$2bo$3o!").unwrap_err();
+    assert_eq!(error.line, 1);
+    assert!(error.message.contains("out of range"));
+}
+
+#[test]
+pub fn test_size_limits() {
+    // A run is refused before any of its cells are listed
+    let error = parse_rle("x = 1, y = 1\n4000000000o!").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 11));
+    assert!(error.message.contains(&MAX_RLE_POPULATION.to_string()));
+
+    // Live cells far apart cover too much
+    let error = parse_rle("o$4000000000bo!").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 14));
+    let error = parse_rle("o4294967294$o!").unwrap_err();
+    assert!(error.message.contains(&MAX_RLE_AREA.to_string()));
+
+    let error = parse_rle("x = 4000000000, y = 4000000000\no!").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 1));
+    assert!(error.message.contains(&MAX_RLE_AREA.to_string()));
+
+    // Dead runs on their own are fine
+    let pattern = parse_rle("4000000000b$o!").unwrap();
+    assert_eq!(pattern.live_cells().collect::<Vec<_>>(), vec![(1, 0)]);
+}
@@ -0,0 +1,52 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{Rule, RuleParseError, Universe};
+
+#[test]
+pub fn test_parse_notations() {
+    let conway = Rule::conway();
+    assert_eq!(Rule::parse("B3/S23"), Ok(conway));
+    assert_eq!(Rule::parse("b3/s23"), Ok(conway));
+    assert_eq!(Rule::parse("S23/B3"), Ok(conway));
+    assert_eq!(Rule::parse("B3S23"), Ok(conway));
+    assert_eq!(Rule::parse("23/3"), Ok(conway));
+
+    let high_life: Rule = "B36/S23".parse().unwrap();
+    assert_eq!(high_life, Rule::new(&[3, 6], &[2, 3]));
+    assert_eq!(high_life.to_string(), "B36/S23");
+
+    let seeds = Rule::parse("B2/S").unwrap();
+    assert!(seeds.is_birth(2));
+    assert!(!seeds.is_survival(2));
+}
+
+#[test]
+pub fn test_parse_errors() {
+    assert_eq!(Rule::parse(""), Err(RuleParseError::Empty));
+    assert_eq!(Rule::parse("B3"), Err(RuleParseError::MissingSurvival));
+    assert_eq!(Rule::parse("B3/S23/B4"), Err(RuleParseError::TooManySections));
+    assert_eq!(
+        Rule::parse("B3/S29"),
+        Err(RuleParseError::InvalidNeighborCount { count: '9', position: 5 }));
+    assert_eq!(
+        Rule::parse("B3/Sx"),
+        Err(RuleParseError::InvalidCharacter { character: 'x', position: 4 }));
+    assert_eq!(
+        Rule::parse("B33/S23"),
+        Err(RuleParseError::DuplicateNeighborCount { count: '3', position: 2 }));
+}
+
+#[test]
+pub fn test_tick_uses_rule() {
+    // Under Seeds (B2/S) every live cell dies, and a domino gives birth to
+    // the cells on either side of it.
+    let mut universe = Universe::with_rule(6, 6, Rule::parse("B2/S").unwrap());
+    universe.toggle_cell(2, 2);
+    universe.toggle_cell(2, 3);
+    universe.tick();
+
+    let mut expected = Universe::with_rule(6, 6, Rule::parse("B2/S").unwrap());
+    for (row, col) in [(1, 2), (1, 3), (3, 2), (3, 3)].iter().cloned() {
+        expected.toggle_cell(row, col);
+    }
+    assert_eq!(universe, expected);
+}
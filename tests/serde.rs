@@ -0,0 +1,63 @@
+#![cfg(feature = "serde")]
+
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Rule, Topology, Universe};
+
+#[test]
+pub fn test_json_round_trip() {
+    let mut universe = Universe::with_rule_and_topology(13, 7, Rule::parse("B36/S23").unwrap(), Topology::KleinBottle);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 2, 3);
+    universe.tick();
+    universe.tick();
+
+    let json = serde_json::to_string(&universe).unwrap();
+    let loaded: Universe = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, universe);
+    assert_eq!(loaded.generation(), 2);
+    assert_eq!(loaded.rule(), universe.rule());
+    assert_eq!(loaded.topology(), Topology::KleinBottle);
+}
+
+#[test]
+pub fn test_json_format() {
+    let mut universe = Universe::new(4, 3);
+    universe.set_cell(0, 0, true);
+    universe.set_cell(0, 3, true);
+    universe.set_cell(2, 3, true);
+
+    // Cells 0, 3 and 11 are alive: bytes 0b00001001 and 0b00001000
+    assert_eq!(
+        serde_json::to_string(&universe).unwrap(),
+        r#"{"width":4,"height":3,"rule":"B3/S23","topology":"Torus","generation":0,"cells":"CQg="}"#
+    );
+}
+
+#[test]
+pub fn test_rejects_wrong_cell_count() {
+    let json = r#"{"width":4,"height":3,"rule":"B3/S23","topology":"Torus","generation":0,"cells":"CQ=="}"#;
+    let error = serde_json::from_str::<Universe>(json).unwrap_err();
+    assert!(error.to_string().contains("expected 2 bytes"), "{}", error);
+}
+
+#[test]
+pub fn test_rejects_too_many_cells() {
+    let json = r#"{"width":70000,"height":70000,"rule":"B3/S23","topology":"Torus","generation":0,"cells":""}"#;
+    let error = serde_json::from_str::<Universe>(json).unwrap_err();
+    assert!(error.to_string().contains("70000x70000"), "{}", error);
+}
+
+#[test]
+pub fn test_rejects_invalid_rule() {
+    let json = r#"{"width":4,"height":3,"rule":"X3","topology":"Torus","generation":0,"cells":"CQg="}"#;
+    assert!(serde_json::from_str::<Universe>(json).is_err());
+}
+
+#[test]
+pub fn test_large_round_trip() {
+    let mut universe = Universe::new(67, 45);
+    universe.randomize_with(7, 0.4, None);
+
+    let json = serde_json::to_string(&universe).unwrap();
+    assert_eq!(serde_json::from_str::<Universe>(&json).unwrap(), universe);
+}
@@ -0,0 +1,88 @@
+extern crate wasm_game_of_life;
+use array2d::Array2D;
+use wasm_game_of_life::{SizeMismatch, Topology, Universe};
+
+mod common;
+use common::live_cells;
+
+#[test]
+pub fn test_from_array2d() {
+    let rows = vec![
+        vec![0, 1, 0, 0],
+        vec![0, 0, 1, 0],
+        vec![1, 1, 1, 0],
+    ];
+    let mut universe = Universe::new(4, 3);
+    universe.set_cell(0, 3, true);
+    universe.set_cells(Array2D::from_rows(&rows).unwrap()).unwrap();
+    assert_eq!(live_cells(&universe), vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    let mut from_bools = Universe::new(4, 3);
+    let bools = [vec![false, true, false, false], vec![false, false, true, false], vec![true, true, true, false]];
+    from_bools.set_cells(Array2D::from_rows(&bools).unwrap()).unwrap();
+    assert_eq!(from_bools, universe);
+
+    let expected: Vec<Vec<bool>> = rows.iter().map(|row| row.iter().map(|cell| *cell == 1).collect()).collect();
+    assert_eq!(universe.to_array2d().as_rows(), expected);
+}
+
+// A grid of a different size is refused, and changes nothing
+#[test]
+pub fn test_array2d_size_mismatch() {
+    let mut universe = Universe::new(3, 3);
+    universe.set_cell(2, 2, true);
+
+    let error = universe.set_cells(Array2D::filled_with(1u8, 2, 5)).unwrap_err();
+    assert_eq!(error, SizeMismatch::Grid { width: 3, height: 3, rows: 2, columns: 5 });
+    assert_eq!(
+        error.to_string(),
+        "expected 3 rows and 3 columns of cells for a 3x3 universe, not 2 rows and 5 columns"
+    );
+    assert!(universe.set_cells(Array2D::filled_with(1u8, 3, 2)).is_err());
+    assert_eq!(live_cells(&universe), vec![(2, 2)]);
+}
+
+#[test]
+pub fn test_from_row_major() {
+    let mut universe = Universe::new(3, 2);
+    universe.set_cells_row_major(&[true, false, false, false, true, true]).unwrap();
+    assert_eq!(live_cells(&universe), vec![(0, 0), (1, 1), (1, 2)]);
+    assert_eq!(universe.to_array2d().as_row_major(), vec![true, false, false, false, true, true]);
+}
+
+#[test]
+pub fn test_row_major_wrong_length() {
+    let mut universe = Universe::new(3, 2);
+    universe.set_cell(1, 1, true);
+
+    let error = universe.set_cells_row_major(&[true, false]).unwrap_err();
+    assert_eq!(error, SizeMismatch::RowMajor { width: 3, height: 2, len: 2 });
+    assert_eq!(error.to_string(), "expected 6 cells for a 3x2 universe, not 2");
+    assert!(universe.set_cells_row_major(&[true; 7]).is_err());
+    assert_eq!(live_cells(&universe), vec![(1, 1)]);
+}
+
+#[test]
+pub fn test_from_coordinates() {
+    let mut universe = Universe::new(5, 5);
+    universe.set_cell(4, 4, true);
+    universe.set_live_cells(vec![(1, 2), (0, 0), (6, 1)]);
+    assert_eq!(live_cells(&universe), vec![(0, 0), (1, 1), (1, 2)]);
+
+    let mut bounded = Universe::with_topology(5, 5, Topology::Bounded);
+    bounded.set_live_cells([(1, 2), (6, 1)]);
+    assert_eq!(live_cells(&bounded), vec![(1, 2)]);
+}
+
+#[test]
+pub fn test_bulk_edits_undo() {
+    let mut universe = Universe::new(4, 4);
+    universe.set_history_budget(1 << 20);
+    universe.set_live_cells([(0, 0), (1, 1)]);
+    universe.set_cells_row_major(&[true; 16]).unwrap();
+
+    assert!(universe.undo());
+    assert_eq!(live_cells(&universe), vec![(0, 0), (1, 1)]);
+    assert!(universe.undo());
+    assert_eq!(universe.population(), 0);
+}
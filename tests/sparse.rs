@@ -0,0 +1,101 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{parse_rle, Engine, EngineError, Grid, HashLife, Rule, SparseUniverse, Universe};
+
+mod common;
+use common::pattern_cells;
+
+#[test]
+pub fn test_matches_hashlife() {
+    let acorn = parse_rle("x = 7, y = 3\nbo$3bo$2o2b3o!").unwrap();
+    let mut sparse = SparseUniverse::from_pattern(&acorn).unwrap();
+    let mut hashlife = HashLife::from_pattern(&acorn).unwrap();
+
+    for _ in 0..200 {
+        sparse.tick();
+    }
+    hashlife.step(200);
+
+    assert_eq!(sparse.population(), Engine::population(&hashlife));
+    assert_eq!(pattern_cells(&sparse.to_pattern().unwrap()), pattern_cells(&hashlife.to_pattern().unwrap()));
+}
+
+#[test]
+pub fn test_glider_crosses_chunks() {
+    // Heading up and to the left, into negative coordinates
+    let glider = parse_rle("x = 3, y = 3\n3o$o$bo!").unwrap();
+    let mut universe = SparseUniverse::from_pattern(&glider).unwrap();
+
+    for _ in 0..400 {
+        universe.tick();
+    }
+
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.chunk_count(), 1);
+    assert!(universe.cell_at(-100, -100));
+
+    // After 100 periods it has moved 100 cells up and to the left
+    let mut viewport = universe.live_cells_in(-110, -110, 20, 20);
+    viewport.sort();
+    assert_eq!(viewport, vec![(-100, -100), (-100, -99), (-100, -98), (-99, -100), (-98, -99)]);
+    assert!(universe.live_cells_in(0, 0, 64, 64).is_empty());
+}
+
+#[test]
+pub fn test_set_cell_frees_chunks() {
+    let mut universe = SparseUniverse::new(Default::default()).unwrap();
+    universe.set_cell(1000, -1000, true);
+    universe.toggle_cell(5, 5);
+    assert_eq!(universe.chunk_count(), 2);
+
+    universe.set_cell(1000, -1000, false);
+    assert_eq!(universe.chunk_count(), 1);
+    assert_eq!(universe.population(), 1);
+}
+
+#[test]
+pub fn test_cells_too_far_apart_for_a_pattern() {
+    let mut universe = SparseUniverse::new(Default::default()).unwrap();
+    universe.set_cell(0, 0, true);
+    universe.set_cell(0, 5_000_000_000, true);
+    assert_eq!(universe.to_pattern().err(), Some(EngineError::TooLarge));
+
+    universe.set_cell(0, 5_000_000_000, false);
+    universe.set_cell(0, u32::MAX as i64 - 1, true);
+    assert_eq!(universe.to_pattern().unwrap().width(), u32::MAX);
+}
+
+#[test]
+pub fn test_rejects_b0() {
+    let rule = Rule::parse("B0/S8").unwrap();
+    assert_eq!(SparseUniverse::new(rule).err(), Some(EngineError::UnsupportedRule(rule)));
+}
+
+// What a renderer showing rows and columns 0 to 7 would draw.
+fn viewport(grid: &dyn Grid) -> Vec<(i64, i64)> {
+    let mut cells = grid.live_cells_in(0, 0, 8, 8);
+    cells.sort_unstable();
+    cells
+}
+
+#[test]
+pub fn test_grid_is_shared_with_universe() {
+    let glider = parse_rle("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+    let mut universe = Universe::new(32, 32);
+    universe.load_pattern(&glider, 0, 0);
+    let mut sparse = SparseUniverse::from_pattern(&glider).unwrap();
+
+    let grids: [&mut dyn Grid; 2] = [&mut universe, &mut sparse];
+    for grid in grids {
+        grid.step(8);
+        grid.toggle_cell(7, 0);
+        assert!(grid.cell_at(7, 0));
+        assert_eq!(viewport(grid), vec![(2, 3), (3, 4), (4, 2), (4, 3), (4, 4), (7, 0)]);
+        assert_eq!(grid.live_cells_in(-10, -10, 10, 10), vec![]);
+
+        grid.clear();
+        assert_eq!(grid.population(), 0);
+        assert_eq!(grid.generation(), 0);
+    }
+    assert_eq!(universe.size(), Some((32, 32)));
+    assert_eq!(sparse.size(), None);
+}
@@ -0,0 +1,133 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, GenerationStats, Rect, Statistics, Universe};
+
+fn blinker_universe() -> Universe {
+    let mut universe = Universe::new(10, 10);
+    universe.load_pattern(&library_pattern("blinker").unwrap(), 5, 5);
+    universe
+}
+
+#[test]
+pub fn test_statistics_per_generation() {
+    let mut universe = blinker_universe();
+    assert!(universe.statistics().is_none());
+
+    universe.set_statistics_capacity(10);
+    universe.tick();
+    universe.tick();
+
+    let statistics = universe.statistics().unwrap();
+    assert_eq!(statistics.len(), 3);
+    assert_eq!(statistics.series(|stats| stats.generation), [0, 1, 2]);
+    assert_eq!(statistics.series(|stats| stats.population), [3, 3, 3]);
+    assert_eq!(statistics.series(|stats| stats.births), [0, 2, 2]);
+    assert_eq!(statistics.series(|stats| stats.deaths), [0, 2, 2]);
+    assert_eq!(
+        statistics.series(|stats| stats.bounding_box),
+        [Some(Rect::new(5, 5, 3, 1)), Some(Rect::new(4, 6, 1, 3)), Some(Rect::new(5, 5, 3, 1))]
+    );
+    assert_eq!(statistics.latest().unwrap().density, 0.03);
+}
+
+#[test]
+pub fn test_ring_buffer_keeps_latest() {
+    let mut universe = blinker_universe();
+    universe.set_statistics_capacity(4);
+    for _ in 0..10 {
+        universe.tick();
+    }
+
+    let statistics = universe.statistics().unwrap();
+    assert_eq!(statistics.capacity(), 4);
+    assert_eq!(statistics.series(|stats| stats.generation), [7, 8, 9, 10]);
+
+    // Shrinking keeps the most recent
+    universe.set_statistics_capacity(2);
+    assert_eq!(universe.statistics().unwrap().series(|stats| stats.generation), [9, 10]);
+
+    universe.set_statistics_capacity(0);
+    assert!(universe.statistics().is_none());
+}
+
+#[test]
+pub fn test_going_back_drops_later_generations() {
+    let mut universe = blinker_universe();
+    universe.set_history_budget(1 << 20);
+    universe.set_statistics_capacity(10);
+    universe.tick();
+    universe.tick();
+    universe.step_back();
+
+    let statistics = universe.statistics().unwrap();
+    assert_eq!(statistics.series(|stats| stats.generation), [0, 1]);
+
+    universe.clear();
+    let statistics = universe.statistics().unwrap();
+    assert_eq!(statistics.series(|stats| stats.generation), [0]);
+    assert_eq!(statistics.latest().unwrap().population, 0);
+    assert_eq!(statistics.latest().unwrap().bounding_box, None);
+}
+
+#[test]
+pub fn test_edits_are_measured() {
+    let mut universe = Universe::new(10, 10);
+    universe.set_statistics_capacity(10);
+    let population = |universe: &Universe| universe.statistics().unwrap().latest().unwrap().population;
+
+    universe.set_cell(2, 3, true);
+    assert_eq!(universe.population(), 1);
+    assert_eq!(population(&universe), 1);
+    assert_eq!(universe.statistics().unwrap().latest().unwrap().bounding_box, Some(Rect::new(2, 3, 1, 1)));
+
+    universe.toggle_cell(4, 4);
+    assert_eq!(population(&universe), 2);
+
+    universe.load_pattern(&library_pattern("blinker").unwrap(), 6, 6);
+    assert_eq!(population(&universe), 5);
+
+    universe.randomize();
+    assert_eq!(population(&universe), universe.population());
+
+    universe.tick();
+    universe.toggle_cell(0, 0);
+    let statistics = universe.statistics().unwrap();
+    assert_eq!(statistics.series(|stats| stats.generation), [0, 1]);
+    assert_eq!(population(&universe), universe.population());
+
+    universe.clear();
+    assert_eq!(population(&universe), 0);
+}
+
+#[test]
+pub fn test_export() {
+    let mut statistics = Statistics::new(4);
+    statistics.push(GenerationStats {
+        generation: 0,
+        population: 3,
+        births: 0,
+        deaths: 0,
+        bounding_box: Some(Rect::new(5, 5, 3, 1)),
+        density: 0.03,
+    });
+    statistics.push(GenerationStats {
+        generation: 1,
+        population: 0,
+        births: 0,
+        deaths: 3,
+        bounding_box: None,
+        density: 0.0,
+    });
+
+    assert_eq!(
+        statistics.to_csv(),
+        "generation,population,births,deaths,top,left,width,height,density\n\
+         0,3,0,0,5,5,3,1,0.03\n\
+         1,0,0,3,,,,,0\n"
+    );
+    assert_eq!(
+        statistics.to_json(),
+        "[{\"generation\":0,\"population\":3,\"births\":0,\"deaths\":0,\
+         \"bounding_box\":{\"top\":5,\"left\":5,\"width\":3,\"height\":1},\"density\":0.03},\
+         {\"generation\":1,\"population\":0,\"births\":0,\"deaths\":3,\"bounding_box\":null,\"density\":0}]"
+    );
+}
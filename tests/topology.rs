@@ -0,0 +1,64 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{Topology, Universe};
+
+#[test]
+pub fn test_wrap() {
+    assert_eq!(Topology::Torus.wrap(-1, 6, 6, 4), Some((3, 0)));
+    assert_eq!(Topology::Torus.wrap(4, -1, 6, 4), Some((0, 5)));
+    assert_eq!(Topology::Bounded.wrap(0, 6, 6, 4), None);
+    assert_eq!(Topology::Bounded.wrap(3, 5, 6, 4), Some((3, 5)));
+    assert_eq!(Topology::KleinBottle.wrap(-1, 1, 6, 4), Some((3, 4)));
+    assert_eq!(Topology::KleinBottle.wrap(1, -1, 6, 4), Some((1, 5)));
+    assert_eq!(Topology::CrossSurface.wrap(1, -1, 6, 4), Some((2, 5)));
+    assert_eq!(Topology::CrossSurface.wrap(4, 1, 6, 4), Some((0, 4)));
+}
+
+#[test]
+pub fn test_torus_wraps_right_and_bottom_edges() {
+    // A vertical blinker on the last column becomes a horizontal blinker
+    // that straddles the right edge.
+    let mut universe = Universe::with_topology(5, 5, Topology::Torus);
+    for row in 1..4 {
+        universe.toggle_cell(row, 4);
+    }
+    universe.tick();
+
+    assert!(universe.cell_at(2, 3));
+    assert!(universe.cell_at(2, 4));
+    assert!(universe.cell_at(2, 0));
+    assert!(!universe.cell_at(1, 4));
+}
+
+#[test]
+pub fn test_bounded_edges_are_dead() {
+    // A blinker along the bottom edge loses the cells that would be born
+    // outside the grid, leaving a domino that then dies.
+    let mut universe = Universe::with_topology(5, 5, Topology::Bounded);
+    for col in 1..4 {
+        universe.toggle_cell(4, col);
+    }
+    universe.tick();
+
+    assert!(universe.cell_at(3, 2));
+    assert!(universe.cell_at(4, 2));
+    assert!(!universe.cell_at(0, 2));
+
+    universe.tick();
+    assert!(!universe.cell_at(3, 2));
+    assert!(!universe.cell_at(4, 2));
+}
+
+#[test]
+pub fn test_klein_bottle_mirrors_across_top_edge() {
+    // A blinker on the top row sends its births across the top edge with the
+    // columns mirrored.
+    let mut universe = Universe::with_topology(6, 5, Topology::KleinBottle);
+    for col in 0..3 {
+        universe.toggle_cell(0, col);
+    }
+    universe.tick();
+
+    assert!(universe.cell_at(1, 1));
+    assert!(universe.cell_at(4, 4));
+    assert!(!universe.cell_at(4, 1));
+}
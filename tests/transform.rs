@@ -0,0 +1,102 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Overflow, Rect, Rotation, Universe};
+
+mod common;
+use common::live_cells;
+
+// An L shape in a 5 wide, 3 high universe:
+// O....
+// O....
+// OO...
+fn l_universe() -> Universe {
+    let mut universe = Universe::new(5, 3);
+    for (row, column) in [(0, 0), (1, 0), (2, 0), (2, 1)].iter() {
+        universe.set_cell(*row, *column, true);
+    }
+    universe
+}
+
+#[test]
+pub fn test_shift() {
+    let mut universe = l_universe();
+    universe.shift(1, -1, Overflow::Wrap);
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 4), (1, 4), (2, 4)]);
+
+    let mut universe = l_universe();
+    universe.shift(1, 3, Overflow::Clip);
+    assert_eq!(live_cells(&universe), [(1, 3), (2, 3)]);
+}
+
+#[test]
+pub fn test_rotate() {
+    let mut universe = l_universe();
+    universe.rotate(Rotation::Clockwise90);
+    assert_eq!((universe.width(), universe.height()), (3, 5));
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 1), (0, 2), (1, 0)]);
+
+    universe.rotate(Rotation::Clockwise270);
+    assert_eq!(universe, l_universe());
+
+    universe.rotate(Rotation::Clockwise180);
+    assert_eq!((universe.width(), universe.height()), (5, 3));
+    assert_eq!(live_cells(&universe), [(0, 3), (0, 4), (1, 4), (2, 4)]);
+}
+
+#[test]
+pub fn test_flip() {
+    let mut universe = l_universe();
+    universe.flip_horizontal();
+    assert_eq!(live_cells(&universe), [(0, 4), (1, 4), (2, 3), (2, 4)]);
+
+    let mut universe = l_universe();
+    universe.flip_vertical();
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 1), (1, 0), (2, 0)]);
+}
+
+#[test]
+pub fn test_crop_and_resize() {
+    let mut universe = Universe::new(20, 20);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 5, 7);
+    universe.tick();
+
+    assert_eq!(universe.crop_to_live(), Some(Rect::new(6, 7, 3, 3)));
+    assert_eq!((universe.width(), universe.height()), (3, 3));
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 2), (1, 1), (1, 2), (2, 1)]);
+
+    universe.resize(6, 4);
+    assert_eq!((universe.width(), universe.height()), (6, 4));
+    assert_eq!(live_cells(&universe), [(0, 0), (0, 2), (1, 1), (1, 2), (2, 1)]);
+
+    universe.resize(2, 2);
+    assert_eq!(live_cells(&universe), [(0, 0), (1, 1)]);
+
+    let mut empty = Universe::new(4, 4);
+    assert_eq!(empty.crop_to_live(), None);
+    assert_eq!((empty.width(), empty.height()), (4, 4));
+}
+
+#[test]
+pub fn test_transforms_keep_generation_and_undo() {
+    let mut universe = Universe::new(8, 6);
+    universe.set_history_budget(1 << 20);
+    universe.load_pattern(&library_pattern("glider").unwrap(), 1, 1);
+    universe.tick();
+    let before = format!("{}", universe);
+
+    universe.rotate(Rotation::Clockwise90);
+    universe.shift(2, 2, Overflow::Wrap);
+    universe.resize(10, 10);
+    universe.flip_vertical();
+    assert_eq!(universe.generation(), 1);
+
+    // The glider still flies after being turned around
+    universe.tick();
+    assert_eq!(universe.generation(), 2);
+    assert_eq!(wasm_game_of_life::Engine::population(&universe), 5);
+
+    for _ in 0..5 {
+        assert!(universe.undo());
+    }
+    assert_eq!((universe.width(), universe.height()), (8, 6));
+    assert_eq!(format!("{}", universe), before);
+}
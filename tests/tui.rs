@@ -0,0 +1,42 @@
+#![cfg(feature = "tui")]
+
+use std::process::Command;
+
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{library_pattern, Grid, SparseUniverse, Universe};
+
+// The viewer itself needs a terminal, so only its arguments are run here
+#[test]
+pub fn test_arguments() {
+    let output = Command::new(env!("CARGO_BIN_EXE_life-tui")).arg("--help").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().starts_with("Usage: life-tui"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_life-tui")).args(["--size", "ten"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("invalid size ten"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_life-tui")).args(["--unbounded", "--size", "8x8"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("unbounded universe has no size"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_life-tui")).arg("does-not-exist.rle").output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("couldn't read does-not-exist.rle"));
+}
+
+// The rows of text the viewer draws each frame from
+#[test]
+pub fn test_render_frame() {
+    let glider = library_pattern("glider").unwrap();
+    let mut universe = Universe::new(5, 4);
+    universe.load_pattern(&glider, 0, 1);
+
+    let frame = universe.text_rows(0, 0, 4, 5);
+    assert_eq!(frame, ["◻◻◼◻◻", "◻◻◻◼◻", "◻◼◼◼◻", "◻◻◻◻◻"]);
+    assert_eq!(universe.to_string(), frame.join("\n") + "\n");
+
+    // A view scrolled past the edges of an unbounded universe
+    let sparse = SparseUniverse::from_pattern(&glider).unwrap();
+    assert_eq!(sparse.text_rows(-1, 1, 3, 3), ["◻◻◻", "◼◻◻", "◻◼◻"]);
+}
@@ -0,0 +1,84 @@
+extern crate wasm_game_of_life;
+use wasm_game_of_life::{Engine, Random, Rect, Rule, Topology, Universe, UniverseTooLarge};
+
+#[test]
+pub fn test_randomize_natively() {
+    let mut universe = Universe::new(64, 64);
+    universe.randomize();
+
+    let population = Engine::population(&universe);
+    assert!(population > 0 && population < 64 * 64);
+}
+
+#[test]
+pub fn test_randomize_with_seed_is_repeatable() {
+    let mut first = Universe::new(64, 64);
+    first.randomize_with(42, 0.5, None);
+    let mut second = Universe::new(64, 64);
+    second.randomize_with(42, 0.5, None);
+    assert_eq!(first, second);
+
+    let mut other = Universe::new(64, 64);
+    other.randomize_with(43, 0.5, None);
+    assert_ne!(first, other);
+}
+
+#[test]
+pub fn test_randomize_returns_seed() {
+    let mut universe = Universe::new(32, 32);
+    let seed = universe.randomize();
+
+    let mut replay = Universe::new(32, 32);
+    replay.randomize_with(seed, 0.5, None);
+    assert_eq!(universe, replay);
+}
+
+#[test]
+pub fn test_randomize_density() {
+    let mut universe = Universe::new(100, 100);
+
+    universe.randomize_with(7, 0.0, None);
+    assert_eq!(Engine::population(&universe), 0);
+
+    universe.randomize_with(7, 1.0, None);
+    assert_eq!(Engine::population(&universe), 100 * 100);
+
+    universe.randomize_with(7, 0.2, None);
+    let population = Engine::population(&universe);
+    assert!(population > 1500 && population < 2500, "population {}", population);
+}
+
+#[test]
+pub fn test_randomize_region() {
+    let mut universe = Universe::new(20, 20);
+    universe.set_cell(0, 0, true);
+    universe.randomize_with(1, 1.0, Some(Rect::new(5, 6, 4, 3)));
+
+    assert_eq!(Engine::population(&universe), 1 + 4 * 3);
+    assert!(universe.cell_at(0, 0));
+    assert!(universe.cell_at(5, 6));
+    assert!(universe.cell_at(7, 9));
+    assert!(!universe.cell_at(8, 9));
+    assert!(!universe.cell_at(7, 10));
+
+    // Regions hanging off the edge are clipped
+    universe.randomize_with(1, 1.0, Some(Rect::new(18, 18, 10, 10)));
+    assert_eq!(Engine::population(&universe), 1 + 4 * 3 + 4);
+}
+
+#[test]
+pub fn test_random_sequence_is_portable() {
+    // SplitMix64's published first outputs for seed 0
+    let mut random = Random::new(0);
+    assert_eq!(random.next_u64(), 0xE220_A839_7B1D_CDAF);
+    assert_eq!(random.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+}
+
+#[test]
+pub fn test_too_large() {
+    let error = Universe::try_with_rule_and_topology(70000, 70000, Rule::conway(), Topology::Torus).err();
+    assert_eq!(error, Some(UniverseTooLarge { width: 70000, height: 70000 }));
+
+    let universe = Universe::try_with_rule_and_topology(65535, 3, Rule::conway(), Topology::Bounded).unwrap();
+    assert_eq!((universe.width(), universe.height()), (65535, 3));
+}
@@ -9,7 +9,7 @@ use array2d::Array2D;
 wasm_bindgen_test_configure!(run_in_browser);
 
 extern crate wasm_game_of_life;
-use wasm_game_of_life::Universe;
+use wasm_game_of_life::{JsHashLife, JsUniverse, PatternFormat, Topology, Universe};
 
 #[cfg(test)]
 pub fn input_spaceship() -> Universe {
@@ -22,7 +22,7 @@ pub fn input_spaceship() -> Universe {
     	vec![0, 0, 0, 0, 0, 0],
     	vec![0, 0, 0, 0, 0, 0],
     ];
-    universe.set_cells(Array2D::from_rows(&rows));
+    universe.set_cells(Array2D::from_rows(&rows).unwrap()).unwrap();
     universe
 }
 
@@ -37,7 +37,7 @@ pub fn expected_spaceship() -> Universe {
     	vec![0, 0, 1, 0, 0, 0],
     	vec![0, 0, 0, 0, 0, 0],
     ];
-    universe.set_cells(Array2D::from_rows(&rows));
+    universe.set_cells(Array2D::from_rows(&rows).unwrap()).unwrap();
     universe
 }
 
@@ -61,4 +61,48 @@ pub fn test_tick() {
     input_universe.tick();
 
     assert_eq!(&input_universe, &expected_universe);
-}
\ No newline at end of file
+}
+
+#[wasm_bindgen_test]
+pub fn test_js_universe() {
+    let mut universe = JsUniverse::new(6, 6, Some("B3/S23".to_string()), Some(Topology::Torus)).unwrap();
+    universe.load_pattern("bo$2bo$3o!", 1, 1).unwrap();
+    universe.tick();
+
+    assert_eq!(universe.generation(), 1.0);
+    assert_eq!(universe.population(), 5.0);
+    assert_eq!(universe.universe(), &expected_spaceship());
+    assert_eq!(universe.save_pattern(Some(PatternFormat::Plaintext)), "O.O\n.OO\n.O.\n");
+}
+
+#[wasm_bindgen_test]
+pub fn test_js_cells() {
+    let mut universe = JsUniverse::new(5, 3, None, None).unwrap();
+    universe.set_cell(0, 0, true);
+    universe.set_cell(0, 3, true);
+    universe.set_cell(2, 4, true);
+
+    assert_eq!(universe.cells().to_vec(), vec![0b0000_1001, 0b0100_0000]);
+    assert_eq!(universe.cells_len(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_js_errors() {
+    assert!(JsUniverse::new(6, 6, Some("B3/X".to_string()), None).is_err());
+
+    let mut universe = JsUniverse::new(6, 6, None, None).unwrap();
+    assert!(universe.set_rule("nonsense").is_err());
+    assert_eq!(universe.rule(), "B3/S23");
+}
+
+#[wasm_bindgen_test]
+pub fn test_js_hashlife() {
+    let mut hashlife = JsHashLife::new(None).unwrap();
+    hashlife.load_pattern("b2o$2o$bo!", 0.0, 0.0).unwrap();
+    hashlife.step(1103.0).unwrap();
+
+    assert_eq!(hashlife.generation(), 1103.0);
+    assert_eq!(hashlife.population(), 116.0);
+    assert!(hashlife.step_power_of_two(63).is_err());
+    assert!(JsHashLife::new(Some("B0/S8".to_string())).is_err());
+}
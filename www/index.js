@@ -1,4 +1,4 @@
-import { Universe, PanicHook, draw_grid, draw_cells, render_loop, start } from "wasm-game-of-life";
+import { PanicHook, start } from "wasm-game-of-life";
 
 PanicHook.set_panic_hook();
 